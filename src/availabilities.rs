@@ -1,39 +1,168 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use itertools::Itertools;
+use serde::{Serialize, Serializer};
 use time::Date;
 
 use crate::calendar::Event;
+use crate::error::AubepineError;
+use crate::utils::DateExt;
 
-#[derive(Debug, Clone)]
+/// Patterns allowed instead of the cells of a row, e.g. `Alice,1ère SF jour,PATTERN,MON-FRI`.
+const PATTERNS: [&str; 6] = ["MON-FRI", "SAT-SUN", "WEEKDAYS", "WEEKENDS", "ALL", "NONE"];
+
+#[derive(Debug, Clone, Default)]
 pub struct Availabilities {
     days: HashMap<Date, Vec<Event>>,
 }
 
+/// The events available on each day, by date: `{ "2025-05-05": ["FirstDaily"], "2025-05-06": [] }`
+impl Serialize for Availabilities {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.days
+            .iter()
+            .map(|(day, events)| (day.to_string(), events))
+            .collect::<BTreeMap<_, _>>()
+            .serialize(serializer)
+    }
+}
+
 impl Availabilities {
     /// Input must contain the name of the person, the level of on-call, and the availabilities, each separated by a comma.
-    /// When available, the cell is empty. When not available, there could be 'x', 'v', 'X' or 'V'.
-    pub fn from_str(from: Date, line: &str) -> Self {
-        Self {
-            days: Self::map_from_str(from, line),
+    /// When available, the cell is empty. Any other content means not available: 'x', 'v', 'OFF', 'ABS'...
+    /// A '1' means the person is already on-call that day, see `parse_initial_allocations`.
+    /// The availabilities after `to` are ignored, and a warning is returned if there are any.
+    pub fn from_str(from: Date, to: Date, line: &str) -> (Self, Option<String>) {
+        let (days, warning) = Self::map_from_str(from, to, line);
+        (Self { days }, warning)
+    }
+
+    /// No day available, see `set_available`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make the person available for `events` on `day`, in addition to the ones she's already available for.
+    pub fn set_available(&mut self, day: Date, events: &[Event]) {
+        self.extend(Self {
+            days: HashMap::from([(day, events.to_vec())]),
+        });
+    }
+
+    /// Parse the VEVENT blocks of an iCalendar, and mark all the days they span as unavailable.
+    /// Only the days covered by an event are present in the returned availabilities, all with no event available.
+    pub fn from_ical(ical_str: &str) -> Result<Self, AubepineError> {
+        let mut days = HashMap::new();
+        let mut in_event = false;
+        let mut start = None;
+        let mut end = None;
+        for line in unfold_ical_lines(ical_str) {
+            let (property, value) = line.split_once(':').unwrap_or((&line, ""));
+            // Parameters, such as the time zone, are ignored as only the days matter
+            let property_name = property.split_once(';').map_or(property, |(name, _)| name);
+            match property_name.to_ascii_uppercase().as_str() {
+                "BEGIN" if value.eq_ignore_ascii_case("VEVENT") => {
+                    in_event = true;
+                    start = None;
+                    end = None;
+                }
+                "DTSTART" if in_event => start = Some(parse_ical_date(value)?),
+                "DTEND" if in_event => end = Some(parse_ical_date(value)?),
+                "END" if value.eq_ignore_ascii_case("VEVENT") => {
+                    if !in_event {
+                        return Err(AubepineError::InvalidICal(
+                            "END:VEVENT without BEGIN:VEVENT".to_string(),
+                        ));
+                    }
+                    in_event = false;
+                    let (first_day, _) = start.ok_or_else(|| {
+                        AubepineError::InvalidICal("VEVENT without DTSTART".to_string())
+                    })?;
+                    // The end is exclusive: an event ending at midnight doesn't block the following day
+                    let last_day = match end {
+                        Some((day, true)) if day > first_day => day.previous_day().unwrap(),
+                        Some((day, _)) => day,
+                        None => first_day,
+                    };
+                    let mut day = first_day;
+                    while day <= last_day {
+                        days.insert(day, vec![]);
+                        day = day.next_day().unwrap();
+                    }
+                }
+                _ => {}
+            }
         }
+        if in_event {
+            return Err(AubepineError::InvalidICal(
+                "BEGIN:VEVENT without END:VEVENT".to_string(),
+            ));
+        }
+        Ok(Self { days })
+    }
+
+    /// Same as `from_str`, but adding the availabilities to the existing ones.
+    /// If the same event is given twice, the person is available on the days where any of the lines says so.
+    pub fn merge(&mut self, from: Date, to: Date, line: &str) -> Option<String> {
+        let (days, warning) = Self::map_from_str(from, to, line);
+        self.extend(Self { days });
+        warning
     }
 
-    pub fn merge(&mut self, from: Date, line: &str) {
-        let new_map = Self::map_from_str(from, line);
-        for (day, availabilities) in new_map {
+    /// Add the availabilities of `other` to these ones: the person is available on the days where any of them says so.
+    pub fn extend(&mut self, other: Self) {
+        for (day, availabilities) in other.days {
             self.days
                 .entry(day)
-                .and_modify(|v| v.extend(availabilities.clone()))
+                .and_modify(|v| {
+                    // No duplicate, otherwise the event would still be available once popped
+                    for event in &availabilities {
+                        if !v.contains(event) {
+                            v.push(*event);
+                        }
+                    }
+                })
                 .or_insert(availabilities);
         }
     }
 
+    /// Remove the days before `from` or after `to`.
+    pub fn trim(&mut self, from: Date, to: Date) {
+        self.days.retain(|day, _| day.is_in_range(from, to));
+    }
+
+    /// Return the fraction of the days the person is available for the event: 0.0 if never, 1.0 if always.
+    pub fn coverage_density(&self, event: Event) -> f64 {
+        if self.days.is_empty() {
+            return 0.0;
+        }
+        let available_days = self
+            .days
+            .values()
+            .filter(|events| events.contains(&event))
+            .count();
+        available_days as f64 / self.days.len() as f64
+    }
+
+    /// Return the number of days covered, whether the person is available or not.
+    pub fn day_count(&self) -> usize {
+        self.days.len()
+    }
+
+    /// Return the first and last days covered, if any.
+    pub fn date_range(&self) -> Option<(Date, Date)> {
+        Some((*self.days.keys().min()?, *self.days.keys().max()?))
+    }
+
+    /// Return the number of days / events the person is available for, whatever the event.
+    pub fn slot_count(&self) -> usize {
+        self.days.values().map(|events| events.len()).sum()
+    }
+
     pub fn get(&self, day: &Date) -> Option<&Vec<Event>> {
         self.days.get(day)
     }
 
-    #[allow(dead_code)] // Used in unit tests only
     pub fn get_all(&self) -> &HashMap<Date, Vec<Event>> {
         &self.days
     }
@@ -53,6 +182,20 @@ impl Availabilities {
         popped
     }
 
+    /// Remove the event passed in argument from all the days.
+    pub fn pop_event_for_all_days(&mut self, event: Event) {
+        for day in self.days.keys().cloned().collect::<Vec<Date>>() {
+            self.pop_event(&day, event);
+        }
+    }
+
+    /// Remove the events not in `allowed` from all the days.
+    pub fn restrict_to_events(&mut self, allowed: &[Event]) {
+        for events in self.days.values_mut() {
+            events.retain(|event| allowed.contains(event));
+        }
+    }
+
     pub fn parse_initial_allocations(from: Date, line: &str) -> HashMap<Date, Event> {
         let mut on_calls = HashMap::new();
         let mut day = from;
@@ -66,17 +209,28 @@ impl Availabilities {
         on_calls
     }
 
-    fn map_from_str(from: Date, line: &str) -> HashMap<Date, Vec<Event>> {
+    fn map_from_str(
+        from: Date,
+        to: Date,
+        line: &str,
+    ) -> (HashMap<Date, Vec<Event>>, Option<String>) {
         let mut days = HashMap::new();
         let mut day = from;
         let (availabilities_str, level) = extract_availability_info(line);
+        if let Some(pattern) = availabilities_str
+            .strip_prefix("PATTERN")
+            .and_then(|rest| rest.strip_prefix([',', ';']))
+        {
+            return Self::map_from_pattern(from, to, pattern.trim(), level);
+        }
+        let mut ignored = 0;
         for token in availabilities_str.split([',', ';']) {
-            let token_lower_case = token.to_ascii_lowercase();
-            let is_available = token.is_empty()
-                || token_lower_case == "p"
-                || token_lower_case == "pj"
-                || token_lower_case == "pn"
-                || token_lower_case == "1";
+            if day > to {
+                ignored += 1;
+                continue;
+            }
+            // An empty cell means available, any text (x, X, v, OFF, ABS, p, 1...) means unavailable
+            let is_available = token.trim().is_empty();
             if is_available {
                 days.entry(day)
                     .and_modify(|v: &mut Vec<Event>| v.push(level))
@@ -86,7 +240,53 @@ impl Availabilities {
             }
             day = day.next_day().unwrap();
         }
-        days
+        let warning = (ignored > 0).then(|| {
+            format!(
+                "{} availabilities after {} are ignored for {:?}",
+                ignored, to, level
+            )
+        });
+        (days, warning)
+    }
+
+    /// Expand a pattern, such as `MON-FRI`, into the availabilities of each day from `from` to `to`.
+    /// An unknown pattern makes the person unavailable every day, with a warning.
+    fn map_from_pattern(
+        from: Date,
+        to: Date,
+        pattern: &str,
+        level: Event,
+    ) -> (HashMap<Date, Vec<Event>>, Option<String>) {
+        let is_weekend = |day: Date| {
+            day.weekday() == time::Weekday::Saturday || day.weekday() == time::Weekday::Sunday
+        };
+        let is_available: Option<fn(bool) -> bool> = match pattern.to_ascii_uppercase().as_str() {
+            "MON-FRI" | "WEEKDAYS" => Some(|weekend| !weekend),
+            "SAT-SUN" | "WEEKENDS" => Some(|weekend| weekend),
+            "ALL" => Some(|_| true),
+            "NONE" => Some(|_| false),
+            _ => None,
+        };
+        let warning = is_available.is_none().then(|| {
+            format!(
+                "Unknown pattern '{}' for {:?} (expected one of: {})",
+                pattern,
+                level,
+                PATTERNS.join(", ")
+            )
+        });
+        let mut days = HashMap::new();
+        let mut day = from;
+        while day <= to {
+            let events = if is_available.is_some_and(|is_available| is_available(is_weekend(day))) {
+                vec![level]
+            } else {
+                vec![]
+            };
+            days.insert(day, events);
+            day = day.next_day().unwrap();
+        }
+        (days, warning)
     }
 
     /// Update the availabilities of a person, given the day and the event that has been requested.
@@ -94,8 +294,7 @@ impl Availabilities {
         let next_day = day + time::Duration::days(1);
         let previous_day = day - time::Duration::days(1);
         her_availabilities.pop_event(&day, event);
-        let is_second_on_the_weekend = (event == Event::SecondDaily
-            || event == Event::SecondNightly)
+        let is_second_on_the_weekend = event.level() == 2
             && (day.weekday() == time::Weekday::Friday
                 || day.weekday() == time::Weekday::Saturday
                 || day.weekday() == time::Weekday::Sunday);
@@ -134,12 +333,7 @@ impl Availabilities {
         for day_ordinal in self.days.keys().sorted() {
             let availabilities = self.days.get(day_ordinal).unwrap();
             formatted.push_str(" | ");
-            for event in &[
-                Event::FirstDaily,
-                Event::FirstNightly,
-                Event::SecondDaily,
-                Event::SecondNightly,
-            ] {
+            for event in &Event::ALL {
                 if availabilities.contains(event) {
                     let code = match event {
                         Event::FirstDaily => 'J',
@@ -158,19 +352,52 @@ impl Availabilities {
     }
 }
 
+/// Join the lines folded by the iCalendar format, i.e. the lines starting with a space or a tab are the continuation of the previous one.
+fn unfold_ical_lines(ical_str: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in ical_str.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(previous)) => previous.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Parse an iCalendar date (`20250505`) or date-time (`20250505T080000Z`).
+/// Also return true when the end of an event would be exclusive, i.e. for a date or a date-time at midnight.
+fn parse_ical_date(value: &str) -> Result<(Date, bool), AubepineError> {
+    let invalid_date = || AubepineError::InvalidICal(format!("Invalid date: {}", value));
+    let (date_str, time_str) = value.split_once('T').unwrap_or((value, ""));
+    if date_str.len() != 8 || !date_str.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid_date());
+    }
+    let year = date_str[0..4].parse().map_err(|_| invalid_date())?;
+    let month: u8 = date_str[4..6].parse().map_err(|_| invalid_date())?;
+    let day = date_str[6..8].parse().map_err(|_| invalid_date())?;
+    let month = time::Month::try_from(month).map_err(|_| invalid_date())?;
+    let date = Date::from_calendar_date(year, month, day).map_err(|_| invalid_date())?;
+    let is_midnight = time_str.trim_end_matches('Z') == "000000";
+    Ok((date, time_str.is_empty() || is_midnight))
+}
+
 fn extract_availability_info(line: &str) -> (&str, Event) {
-    let (level_str, availabilities_str) = line.split_once([',', ';']).unwrap();
-    let level = match level_str {
-        "1ère SF jour" => Event::FirstDaily,
-        "1ère SF nuit" => Event::FirstNightly,
-        "2ème SF jour" => Event::SecondDaily,
-        "2ème SF nuit" => Event::SecondNightly,
-        _ => panic!(
-            "Unknown on-call level. Must be within (1ère SF jour..2ème SF nuit): {}",
-            level_str
+    match try_extract_availability_info(line) {
+        Ok(info) => info,
+        Err(_) => panic!(
+            "Unknown on-call level. Must be within (1ère SF jour..2ème SF nuit) or (1st daily..2nd nightly): {}",
+            line.split_once([',', ';'])
+                .map_or(line, |(level_str, _)| level_str)
         ),
-    };
-    (availabilities_str, level)
+    }
+}
+
+/// Same as `extract_availability_info`, but returning an error instead of panicking on an unknown on-call level.
+pub fn try_extract_availability_info(line: &str) -> Result<(&str, Event), AubepineError> {
+    let (level_str, availabilities_str) = line.split_once([',', ';']).unwrap_or((line, ""));
+    let level = Event::from_label(level_str)
+        .ok_or_else(|| AubepineError::UnknownLevel(level_str.to_string(), None))?;
+    Ok((availabilities_str, level))
 }
 
 #[cfg(test)]
@@ -184,10 +411,11 @@ mod tests {
         let str_1n = "1ère SF nuit,x,,x,x,x,x,x,x,";
         let str_2j = "2ème SF jour,x,x,x,,x,x,x,x,";
         let str_2n = "2ème SF nuit,x,x,x,x,,x,x,,x";
-        let mut availabilities = Availabilities::from_str(day_1, str_1j);
-        availabilities.merge(day_1, str_1n);
-        availabilities.merge(day_1, str_2j);
-        availabilities.merge(day_1, str_2n);
+        let day_9 = Date::from_ordinal_date(2025, 9).unwrap();
+        let (mut availabilities, _) = Availabilities::from_str(day_1, day_9, str_1j);
+        availabilities.merge(day_1, day_9, str_1n);
+        availabilities.merge(day_1, day_9, str_2j);
+        availabilities.merge(day_1, day_9, str_2n);
         assert_eq!(availabilities.days.len(), 9);
         // 1D
         let mut day = day_1;
@@ -236,12 +464,219 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_from_ical() {
+        let ical = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+            BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250505\r\nDTEND;VALUE=DATE:20250507\r\nSUMMARY:Holi\r\n days\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nDTSTART:20250510T180000Z\r\nDTEND:20250511T020000Z\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nDTSTART;TZID=Europe/Paris:20250520T090000\r\nEND:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        let availabilities = Availabilities::from_ical(ical).unwrap();
+        let day = |d| Date::from_calendar_date(2025, time::Month::May, d).unwrap();
+        let blocked_days = availabilities
+            .days
+            .keys()
+            .sorted()
+            .cloned()
+            .collect::<Vec<Date>>();
+        // All-day events have an exclusive end
+        assert_eq!(
+            blocked_days,
+            vec![day(5), day(6), day(10), day(11), day(20)]
+        );
+        assert!(availabilities.days.values().all(|events| events.is_empty()));
+
+        assert!(Availabilities::from_ical("BEGIN:VEVENT\r\nDTSTART:2025\r\nEND:VEVENT").is_err());
+        assert!(
+            Availabilities::from_ical("BEGIN:VEVENT\r\nSUMMARY:No start\r\nEND:VEVENT").is_err()
+        );
+        assert!(Availabilities::from_ical("BEGIN:VEVENT\r\nDTSTART:20250505").is_err());
+    }
+
+    #[test]
+    fn test_availabilities_after_the_last_day() {
+        let day_1 = Date::from_ordinal_date(2025, 1).unwrap();
+        let day_2 = Date::from_ordinal_date(2025, 2).unwrap();
+        let (mut availabilities, warning) =
+            Availabilities::from_str(day_1, day_2, "1ère SF jour,,x");
+        assert_eq!(warning, None);
+        let warning = availabilities.merge(day_1, day_2, "1ère SF nuit,,,x,");
+        assert_eq!(
+            warning,
+            Some("2 availabilities after 2025-01-02 are ignored for FirstNightly".to_string())
+        );
+        assert_eq!(availabilities.days.len(), 2);
+    }
+
+    #[test]
+    fn test_set_available() {
+        let day_1 = Date::from_ordinal_date(2025, 1).unwrap();
+        let mut availabilities = Availabilities::new();
+        assert_eq!(availabilities.day_count(), 0);
+        availabilities.set_available(day_1, &[Event::FirstDaily]);
+        availabilities.set_available(day_1, &[Event::FirstDaily, Event::SecondNightly]);
+        assert_eq!(
+            availabilities.get(&day_1),
+            Some(&vec![Event::FirstDaily, Event::SecondNightly])
+        );
+    }
+
+    #[test]
+    fn test_merge_same_event_twice() {
+        let day_1 = Date::from_ordinal_date(2025, 1).unwrap();
+        let day_3 = Date::from_ordinal_date(2025, 3).unwrap();
+        let (mut availabilities, _) = Availabilities::from_str(day_1, day_3, "1ère SF jour,,x,x");
+        availabilities.merge(day_1, day_3, "1ère SF jour,,,x");
+        let day_2 = day_1.next_day().unwrap();
+        assert_eq!(
+            availabilities.days.get(&day_1),
+            Some(&vec![Event::FirstDaily])
+        );
+        assert_eq!(
+            availabilities.days.get(&day_2),
+            Some(&vec![Event::FirstDaily])
+        );
+        assert_eq!(availabilities.days.get(&day_3), Some(&vec![]));
+
+        availabilities.pop_event(&day_1, Event::FirstDaily);
+        assert_eq!(availabilities.days.get(&day_1), Some(&vec![]));
+    }
+
+    #[test]
+    fn test_english_labels() {
+        let day_1 = Date::from_ordinal_date(2025, 1).unwrap();
+        let day_2 = Date::from_ordinal_date(2025, 2).unwrap();
+        let (availabilities, _) = Availabilities::from_str(day_1, day_2, "2nd nightly,x,");
+        assert_eq!(
+            availabilities.days.get(&day_2),
+            Some(&vec![Event::SecondNightly])
+        );
+    }
+
+    #[test]
+    fn test_mixed_notation() {
+        let day_1 = Date::from_ordinal_date(2025, 1).unwrap();
+        let day_12 = Date::from_ordinal_date(2025, 12).unwrap();
+        let (availabilities, _) = Availabilities::from_str(
+            day_1,
+            day_12,
+            "1ère SF jour,x,X,OFF,ABS,1,,v, ,pas de J,P,pj,pn",
+        );
+        let available_days = availabilities
+            .days
+            .iter()
+            .filter(|(_, events)| !events.is_empty())
+            .map(|(day, _)| day.ordinal())
+            .sorted()
+            .collect::<Vec<u16>>();
+        assert_eq!(available_days, vec![6, 8]);
+    }
+
+    #[test]
+    fn test_coverage_density() {
+        let day_1 = Date::from_ordinal_date(2025, 1).unwrap();
+        let day_4 = Date::from_ordinal_date(2025, 4).unwrap();
+        let (mut availabilities, _) = Availabilities::from_str(day_1, day_4, "1ère SF jour,,x,,");
+        availabilities.merge(day_1, day_4, "1ère SF nuit,,,,");
+        assert_eq!(availabilities.coverage_density(Event::FirstDaily), 0.75);
+        assert_eq!(availabilities.coverage_density(Event::FirstNightly), 1.0);
+        assert_eq!(availabilities.coverage_density(Event::SecondDaily), 0.0);
+        let empty = Availabilities::from_ical("").unwrap();
+        assert_eq!(empty.coverage_density(Event::FirstDaily), 0.0);
+    }
+
+    #[test]
+    fn test_day_count_and_date_range() {
+        let day_1 = Date::from_ordinal_date(2025, 1).unwrap();
+        let day_4 = Date::from_ordinal_date(2025, 4).unwrap();
+        let (availabilities, _) = Availabilities::from_str(day_1, day_4, "1ère SF jour,,x,,x");
+        assert_eq!(availabilities.day_count(), 4);
+        assert_eq!(availabilities.date_range(), Some((day_1, day_4)));
+        let empty = Availabilities::from_ical("").unwrap();
+        assert_eq!(empty.day_count(), 0);
+        assert_eq!(empty.date_range(), None);
+    }
+
+    #[test]
+    fn test_from_pattern() {
+        // From friday 3rd to monday 6th
+        let friday = Date::from_ordinal_date(2025, 3).unwrap();
+        let saturday = Date::from_ordinal_date(2025, 4).unwrap();
+        let monday = Date::from_ordinal_date(2025, 6).unwrap();
+        let (availabilities, warning) =
+            Availabilities::from_str(friday, monday, "1ère SF jour,PATTERN,MON-FRI");
+        assert_eq!(warning, None);
+        assert_eq!(availabilities.get(&friday), Some(&vec![Event::FirstDaily]));
+        assert_eq!(availabilities.get(&saturday), Some(&vec![]));
+        assert_eq!(availabilities.get(&monday), Some(&vec![Event::FirstDaily]));
+
+        let (mut availabilities, _) =
+            Availabilities::from_str(friday, monday, "2ème SF nuit,PATTERN,weekends");
+        assert_eq!(availabilities.get(&friday), Some(&vec![]));
+        assert_eq!(
+            availabilities.get(&saturday),
+            Some(&vec![Event::SecondNightly])
+        );
+        availabilities.merge(friday, monday, "2ème SF jour;PATTERN;ALL");
+        assert_eq!(
+            availabilities.get(&saturday),
+            Some(&vec![Event::SecondNightly, Event::SecondDaily])
+        );
+        assert_eq!(availabilities.slot_count(), 6);
+
+        let (availabilities, warning) =
+            Availabilities::from_str(friday, monday, "1ère SF nuit,PATTERN,MON-WED");
+        assert_eq!(availabilities.slot_count(), 0);
+        assert!(warning.unwrap().starts_with("Unknown pattern 'MON-WED'"));
+    }
+
+    #[test]
+    fn test_serialize() {
+        let day_1 = Date::from_ordinal_date(2025, 1).unwrap();
+        let day_2 = Date::from_ordinal_date(2025, 2).unwrap();
+        let (availabilities, _) = Availabilities::from_str(day_1, day_2, "1ère SF jour,,x");
+        assert_eq!(
+            serde_json::to_string(&availabilities).unwrap(),
+            r#"{"2025-01-01":["FirstDaily"],"2025-01-02":[]}"#
+        );
+    }
+
+    #[test]
+    fn test_slot_count() {
+        let day_1 = Date::from_ordinal_date(2025, 1).unwrap();
+        let day_4 = Date::from_ordinal_date(2025, 4).unwrap();
+        let (mut availabilities, _) = Availabilities::from_str(day_1, day_4, "1ère SF jour,,x,,");
+        assert_eq!(availabilities.slot_count(), 3);
+        availabilities.merge(day_1, day_4, "2ème SF nuit,x,,,");
+        assert_eq!(availabilities.slot_count(), 6);
+        availabilities.pop_all(&day_1);
+        assert_eq!(availabilities.slot_count(), 5);
+    }
+
+    #[test]
+    fn test_restrict_to_events() {
+        let day_1 = Date::from_ordinal_date(2025, 1).unwrap();
+        let day_3 = Date::from_ordinal_date(2025, 3).unwrap();
+        let (mut availabilities, _) = Availabilities::from_str(day_1, day_3, "1ère SF jour,,x,");
+        availabilities.merge(day_1, day_3, "2ème SF nuit,,,x");
+        availabilities.restrict_to_events(&[Event::FirstDaily, Event::FirstNightly]);
+        assert!(availabilities
+            .get_all()
+            .values()
+            .all(|events| !events.contains(&Event::SecondNightly)));
+        assert_eq!(availabilities.get(&day_1), Some(&vec![Event::FirstDaily]));
+        assert_eq!(availabilities.slot_count(), 2);
+        availabilities.restrict_to_events(&[]);
+        assert_eq!(availabilities.slot_count(), 0);
+    }
+
     #[test]
     fn test_pop_single_event() {
         let day_1 = Date::from_ordinal_date(2025, 1).unwrap();
         let str_1j = "1ère SF jour,,x,x,x,x,x,x,,x";
 
-        let mut availabilities = Availabilities::from_str(day_1, str_1j);
+        let day_9 = Date::from_ordinal_date(2025, 9).unwrap();
+        let (mut availabilities, _) = Availabilities::from_str(day_1, day_9, str_1j);
         let a = availabilities.pop_event(&day_1, Event::FirstDaily);
         assert_eq!(a, Some(Event::FirstDaily));
         assert_eq!(availabilities.days.get(&day_1), Some(&vec![]));
@@ -256,10 +691,11 @@ mod tests {
         let str_1n = "1ère SF nuit,x,x,x,x,x,x,x,x,x";
         let str_2j = "2ème SF jour,,x,x,x,x,x,x,,x";
         let str_2n = "2ème SF nuit,,x,x,x,x,x,x,,x";
-        let mut availabilities = Availabilities::from_str(day_1, str_1j);
-        availabilities.merge(day_1, str_1n);
-        availabilities.merge(day_1, str_2j);
-        availabilities.merge(day_1, str_2n);
+        let day_9 = Date::from_ordinal_date(2025, 9).unwrap();
+        let (mut availabilities, _) = Availabilities::from_str(day_1, day_9, str_1j);
+        availabilities.merge(day_1, day_9, str_1n);
+        availabilities.merge(day_1, day_9, str_2j);
+        availabilities.merge(day_1, day_9, str_2n);
 
         let a = availabilities.pop_event(&day_1, Event::FirstDaily);
         assert_eq!(a, Some(Event::FirstDaily));
@@ -278,16 +714,11 @@ mod tests {
         let str_2j = "2ème SF jour,,,,,";
         let str_2n = "2ème SF nuit,,,,,";
 
-        let mut availabilities = Availabilities::from_str(wednesday, str_1j);
-        availabilities.merge(wednesday, str_1n);
-        availabilities.merge(wednesday, str_2j);
-        availabilities.merge(wednesday, str_2n);
-        let all = vec![
-            Event::FirstDaily,
-            Event::FirstNightly,
-            Event::SecondDaily,
-            Event::SecondNightly,
-        ];
+        let (mut availabilities, _) = Availabilities::from_str(wednesday, sunday, str_1j);
+        availabilities.merge(wednesday, sunday, str_1n);
+        availabilities.merge(wednesday, sunday, str_2j);
+        availabilities.merge(wednesday, sunday, str_2n);
+        let all = Event::ALL.to_vec();
         let second = vec![Event::SecondDaily, Event::SecondNightly];
 
         let mut av_cloned = availabilities.clone();
@@ -383,4 +814,26 @@ mod tests {
         assert_eq!(av_cloned.get(&saturday).unwrap(), &second);
         assert_eq!(av_cloned.get(&sunday).unwrap(), &vec![Event::SecondNightly]);
     }
+
+    #[test]
+    fn test_update_her_availabilities_on_sunday_before_monday() {
+        let saturday = Date::from_ordinal_date(2025, 4).unwrap();
+        let sunday = Date::from_ordinal_date(2025, 5).unwrap();
+        let monday = Date::from_ordinal_date(2025, 6).unwrap();
+
+        let (mut availabilities, _) = Availabilities::from_str(saturday, monday, "1ère SF jour,,,");
+        availabilities.merge(saturday, monday, "1ère SF nuit,,,");
+        availabilities.merge(saturday, monday, "2ème SF jour,,,");
+        availabilities.merge(saturday, monday, "2ème SF nuit,,,");
+        let second = vec![Event::SecondDaily, Event::SecondNightly];
+
+        // Get her on call for Sunday as SecondDaily. The weekend is over on Monday, she's no longer available at all.
+        Availabilities::update_availabilities(&mut availabilities, sunday, Event::SecondDaily);
+        assert_eq!(availabilities.get(&saturday).unwrap(), &second);
+        assert_eq!(
+            availabilities.get(&sunday).unwrap(),
+            &vec![Event::SecondNightly]
+        );
+        assert_eq!(availabilities.get(&monday).unwrap(), &vec![]);
+    }
 }
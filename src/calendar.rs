@@ -3,6 +3,7 @@
 //! The calendar is represented as a BTreeMap from a date to a map from an event to a name.
 //! The interface with Calendar is made of the following methods:
 //! a get_all() method that returns the 4 events and name associated for all the days of the calendar
+//! an events_on_day(day) method that returns the 4 events and name associated with a date
 //! a get_for(day, event) method that returns the name associated with an event and a date
 //! a set_for(day, event) method that sets the name associated with a date and an event
 //! a get_missing() method that returns the dates and events for which there is no name associated.$
@@ -10,11 +11,18 @@
 use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 
+use itertools::Itertools;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
 use time::Date;
 
+#[cfg(feature = "compact")]
+use crate::error::AubepineError;
+#[cfg(feature = "compact")]
+use crate::utils::DateExt;
 use crate::Name;
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Event {
     FirstDaily,
     FirstNightly,
@@ -22,7 +30,96 @@ pub enum Event {
     SecondNightly,
 }
 
-#[derive(Debug, Clone)]
+/// Result of `Calendar::weekly_rotation_report`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RotationAnalysis {
+    /// True if the persons always come back in the same order
+    pub is_round_robin: bool,
+    /// Number of days after which the order of the persons repeats, if a rotation has been found
+    pub rotation_period_days: Option<u8>,
+    /// Days where the person on-call differs from the rotation, with the expected and the actual person
+    pub deviations: Vec<(Date, Name, Name)>,
+}
+
+/// Duration of the daily events, from 08:00 to 20:00
+pub const DAILY_DURATION_HOURS: u8 = 12;
+/// Duration of the nightly events, from 20:00 to 08:00 the next day
+pub const NIGHTLY_DURATION_HOURS: u8 = 12;
+
+impl Event {
+    /// All the events, in the order of the calendar.
+    pub const ALL: [Event; 4] = [
+        Event::FirstDaily,
+        Event::FirstNightly,
+        Event::SecondDaily,
+        Event::SecondNightly,
+    ];
+
+    pub fn duration_hours(&self) -> u8 {
+        match self {
+            Event::FirstDaily | Event::SecondDaily => DAILY_DURATION_HOURS,
+            Event::FirstNightly | Event::SecondNightly => NIGHTLY_DURATION_HOURS,
+        }
+    }
+
+    pub fn is_daily(&self) -> bool {
+        matches!(self, Event::FirstDaily | Event::SecondDaily)
+    }
+
+    pub fn is_nightly(&self) -> bool {
+        matches!(self, Event::FirstNightly | Event::SecondNightly)
+    }
+
+    /// Level of on-call: 1 for the first level, 2 for the second one.
+    pub fn level(&self) -> u8 {
+        match self {
+            Event::FirstDaily | Event::FirstNightly => 1,
+            Event::SecondDaily | Event::SecondNightly => 2,
+        }
+    }
+
+    pub fn is_first_level(&self) -> bool {
+        self.level() == 1
+    }
+
+    pub fn is_second_level(&self) -> bool {
+        self.level() == 2
+    }
+}
+
+/// Language of the on-call level labels, in the CSV file and in the exports.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum Locale {
+    #[default]
+    Fr,
+    En,
+}
+
+impl Event {
+    pub fn to_label(&self, locale: Locale) -> &'static str {
+        match (locale, self) {
+            (Locale::Fr, Event::FirstDaily) => "1ère SF jour",
+            (Locale::Fr, Event::FirstNightly) => "1ère SF nuit",
+            (Locale::Fr, Event::SecondDaily) => "2ème SF jour",
+            (Locale::Fr, Event::SecondNightly) => "2ème SF nuit",
+            (Locale::En, Event::FirstDaily) => "1st daily",
+            (Locale::En, Event::FirstNightly) => "1st nightly",
+            (Locale::En, Event::SecondDaily) => "2nd daily",
+            (Locale::En, Event::SecondNightly) => "2nd nightly",
+        }
+    }
+
+    /// Return the event whose label, in any locale, is `label`.
+    pub fn from_label(label: &str) -> Option<Event> {
+        [Locale::Fr, Locale::En].into_iter().find_map(|locale| {
+            Event::ALL
+                .into_iter()
+                .find(|event| event.to_label(locale) == label)
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Calendar {
     from: Date,
     to: Date,
@@ -44,11 +141,10 @@ impl fmt::Display for Event {
 impl Calendar {
     pub fn new(from: Date, to: Date) -> Self {
         let mut days = BTreeMap::new();
-        for ordinal in from.ordinal()..=to.ordinal() {
-            days.insert(
-                Date::from_ordinal_date(from.year(), ordinal).ok().unwrap(),
-                HashMap::new(),
-            );
+        let mut day = from;
+        while day <= to {
+            days.insert(day, HashMap::new());
+            day = day.next_day().unwrap();
         }
         Self { from, to, days }
     }
@@ -61,13 +157,29 @@ impl Calendar {
         self.to
     }
 
+    /// Iterate over all the days of the calendar, from the first to the last one.
+    pub fn days(&self) -> impl Iterator<Item = Date> {
+        let to = self.to;
+        std::iter::successors(Some(self.from), |day| day.next_day())
+            .take_while(move |day| *day <= to)
+    }
+
+    /// Number of days of the calendar.
+    pub fn day_count(&self) -> usize {
+        self.days().count()
+    }
+
     pub fn get_all(&self) -> &BTreeMap<Date, HashMap<Event, Name>> {
         &self.days
     }
 
-    #[allow(dead_code)] // used in unit tests only
+    /// Return the names associated with each event of the day, if the day is within the calendar.
+    pub fn events_on_day(&self, day: &Date) -> Option<&HashMap<Event, Name>> {
+        self.days.get(day)
+    }
+
     pub fn get_for(&self, day: &Date, event: &Event) -> Option<&Name> {
-        self.days.get(day)?.get(event)
+        self.events_on_day(day)?.get(event)
     }
 
     pub fn set_for(&mut self, day: Date, event: Event, name: Name) {
@@ -83,6 +195,128 @@ impl Calendar {
             });
     }
 
+    /// Remove the person on-call for this day and event, and return her name.
+    pub fn unset_for(&mut self, day: &Date, event: &Event) -> Option<Name> {
+        self.days.get_mut(day)?.remove(event)
+    }
+
+    /// Return the days and events for which the person designated by `name` is on-call, sorted by date.
+    pub fn assignments_for_person(&self, name: &str) -> Vec<(Date, Event)> {
+        let mut assignments = vec![];
+        for (day, on_call) in &self.days {
+            for (event, on_call_name) in on_call.iter().sorted() {
+                if on_call_name == name {
+                    assignments.push((*day, *event));
+                }
+            }
+        }
+        assignments
+    }
+
+    /// Return the number of events each person is on-call for. Only the persons on-call at least once are present.
+    pub fn on_call_count_per_person(&self) -> BTreeMap<&Name, usize> {
+        let mut counts = BTreeMap::new();
+        for name in self.days.values().flat_map(|events| events.values()) {
+            *counts.entry(name).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Return the person on-call the most often, with her number of events.
+    /// When several persons are on-call as often, the first one in alphabetical order is returned.
+    pub fn busiest_person(&self) -> Option<(&Name, usize)> {
+        self.on_call_count_per_person()
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+    }
+
+    /// Return the person on-call the least often, among the persons on-call at least once, with her number of events.
+    /// When several persons are on-call as often, the first one in alphabetical order is returned.
+    pub fn least_busy_person(&self) -> Option<(&Name, usize)> {
+        self.on_call_count_per_person()
+            .into_iter()
+            .min_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(b.0)))
+    }
+
+    /// Return a copy of the calendar where each person is replaced by a code, P01, P02, etc. in order of first on-call,
+    /// to share it without the names. Also return the code of each person, mapped to her name.
+    pub fn anonymize(&self) -> (Calendar, HashMap<Name, Name>) {
+        let mut anonymized = Calendar::new(self.from, self.to);
+        let mut codes = HashMap::new();
+        for (day, on_call) in &self.days {
+            for (event, name) in on_call.iter().sorted() {
+                let code_count = codes.len();
+                let code = codes
+                    .entry(name.clone())
+                    .or_insert_with(|| format!("P{:02}", code_count + 1));
+                anonymized.set_for(*day, *event, code.clone());
+            }
+        }
+        let names = codes.into_iter().map(|(name, code)| (code, name)).collect();
+        (anonymized, names)
+    }
+
+    /// Return the number of hours the person designated by `name` is on-call during the ISO week of `day`.
+    pub fn hours_in_week_for_person(&self, name: &str, day: Date) -> u16 {
+        let (year, week, _) = day.to_iso_week_date();
+        self.assignments_for_person(name)
+            .iter()
+            .filter(|(d, _)| {
+                let (y, w, _) = d.to_iso_week_date();
+                y == year && w == week
+            })
+            .map(|(_, event)| event.duration_hours() as u16)
+            .sum()
+    }
+
+    /// Return the number of hours the person designated by `name` is on-call during the calendar month of `day`.
+    pub fn hours_in_month_for_person(&self, name: &str, day: Date) -> u16 {
+        self.assignments_for_person(name)
+            .iter()
+            .filter(|(d, _)| d.year() == day.year() && d.month() == day.month())
+            .map(|(_, event)| event.duration_hours() as u16)
+            .sum()
+    }
+
+    /// Check whether the persons on-call for `event` form a rotation, i.e. the same persons always come back in the same order.
+    /// The rotation is the one, made of distinct persons, that the calendar deviates the least from. Empty days are ignored.
+    pub fn weekly_rotation_report(&self, event: Event) -> RotationAnalysis {
+        let on_call: Vec<(Date, &Name)> = self
+            .days
+            .iter()
+            .filter_map(|(day, events)| events.get(&event).map(|name| (*day, name)))
+            .collect();
+        let mut best: Option<RotationAnalysis> = None;
+        // A rotation must be seen at least twice to be recognized
+        for period in 2..=(on_call.len() / 2).min(u8::MAX as usize) {
+            let rotation: Vec<&Name> = on_call[..period].iter().map(|(_, name)| *name).collect();
+            if !rotation.iter().all_unique() {
+                continue;
+            }
+            let deviations: Vec<(Date, Name, Name)> = on_call
+                .iter()
+                .enumerate()
+                .filter(|(i, (_, name))| *name != rotation[i % period])
+                .map(|(i, (day, name))| (*day, rotation[i % period].clone(), (*name).clone()))
+                .collect();
+            if best
+                .as_ref()
+                .is_none_or(|b| deviations.len() < b.deviations.len())
+            {
+                best = Some(RotationAnalysis {
+                    is_round_robin: deviations.is_empty(),
+                    rotation_period_days: Some(period as u8),
+                    deviations,
+                });
+            }
+        }
+        best.unwrap_or(RotationAnalysis {
+            is_round_robin: false,
+            rotation_period_days: None,
+            deviations: vec![],
+        })
+    }
+
     pub fn get_empty_days(&self, event: &Event) -> Vec<Date> {
         let mut missing = vec![];
         for (day, on_call) in &self.days {
@@ -92,6 +326,110 @@ impl Calendar {
         }
         missing
     }
+
+    /// Serialize the calendar in a compact binary form, to archive many calendars cheaply. Requires the `compact`
+    /// feature.
+    /// The first and last days are stored as a year (i32) and an ordinal (u16). Then, for each allocated event,
+    /// the number of days since the first one (u16), the event (u8) and the name, as a length (u16) followed by UTF-8.
+    /// All the integers are little endian, so a calendar can't span more than 65536 days, nor a name be longer than
+    /// 65535 bytes.
+    #[cfg(feature = "compact")]
+    pub fn serialize_compact(&self) -> Result<Vec<u8>, AubepineError> {
+        let mut data = Vec::new();
+        for day in [self.from, self.to] {
+            data.extend(day.year().to_le_bytes());
+            data.extend(day.ordinal().to_le_bytes());
+        }
+        for (day, events) in &self.days {
+            let offset = u16::try_from(self.from.days_until(*day))
+                .map_err(|_| AubepineError::InvalidCompact(format!("Day {} too far", day)))?;
+            for (event, name) in events.iter().sorted() {
+                let len = u16::try_from(name.len()).map_err(|_| {
+                    AubepineError::InvalidCompact(format!("Name {} too long", name))
+                })?;
+                data.extend(offset.to_le_bytes());
+                data.push(*event as u8);
+                data.extend(len.to_le_bytes());
+                data.extend(name.as_bytes());
+            }
+        }
+        Ok(data)
+    }
+
+    /// Build back a calendar serialized by `serialize_compact`.
+    #[cfg(feature = "compact")]
+    pub fn deserialize_compact(mut data: &[u8]) -> Result<Calendar, AubepineError> {
+        let mut range = [Date::MIN; 2];
+        for day in range.iter_mut() {
+            let year = i32::from_le_bytes(take(&mut data)?);
+            let ordinal = u16::from_le_bytes(take(&mut data)?);
+            *day = Date::from_ordinal_date(year, ordinal)
+                .map_err(|e| AubepineError::InvalidCompact(e.to_string()))?;
+        }
+        let [from, to] = range;
+        if from > to {
+            return Err(AubepineError::InvalidRange(from, to));
+        }
+        let mut calendar = Calendar::new(from, to);
+        while !data.is_empty() {
+            let offset = u16::from_le_bytes(take(&mut data)?);
+            let day = from
+                .checked_add(time::Duration::days(offset as i64))
+                .ok_or_else(|| AubepineError::InvalidCompact(format!("Day {} too far", offset)))?;
+            if day > to {
+                return Err(AubepineError::DateOutOfRange(day));
+            }
+            let event = match take::<1>(&mut data)? {
+                [0] => Event::FirstDaily,
+                [1] => Event::FirstNightly,
+                [2] => Event::SecondDaily,
+                [3] => Event::SecondNightly,
+                [code] => {
+                    return Err(AubepineError::InvalidCompact(format!(
+                        "Unknown event {}",
+                        code
+                    )))
+                }
+            };
+            let len = u16::from_le_bytes(take(&mut data)?) as usize;
+            if data.len() < len {
+                return Err(AubepineError::InvalidCompact("Truncated name".to_string()));
+            }
+            let (name, rest) = data.split_at(len);
+            let name = String::from_utf8(name.to_vec())
+                .map_err(|e| AubepineError::InvalidCompact(e.to_string()))?;
+            calendar.set_for(day, event, name);
+            data = rest;
+        }
+        Ok(calendar)
+    }
+}
+
+/// Read the next `N` bytes of the compact serialization, and move past them.
+#[cfg(feature = "compact")]
+fn take<const N: usize>(data: &mut &[u8]) -> Result<[u8; N], AubepineError> {
+    if data.len() < N {
+        return Err(AubepineError::InvalidCompact("Truncated data".to_string()));
+    }
+    let (bytes, rest) = data.split_at(N);
+    *data = rest;
+    Ok(bytes.try_into().unwrap())
+}
+
+/// `{ "from": "2025-05-05", "to": "2025-05-06", "days": { "2025-05-05": { "FirstDaily": "Alice" }, "2025-05-06": {} } }`
+impl Serialize for Calendar {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let days = self
+            .days
+            .iter()
+            .map(|(day, events)| (day.to_string(), events.iter().collect::<BTreeMap<_, _>>()))
+            .collect::<BTreeMap<_, _>>();
+        let mut calendar = serializer.serialize_struct("Calendar", 3)?;
+        calendar.serialize_field("from", &self.from.to_string())?;
+        calendar.serialize_field("to", &self.to.to_string())?;
+        calendar.serialize_field("days", &days)?;
+        calendar.end()
+    }
 }
 
 impl fmt::Display for Calendar {
@@ -105,12 +443,7 @@ impl fmt::Display for Calendar {
         s.push_str(format!("{}\r\n", header).as_str());
         // print a line of dashes as long as the line of header
         s.push_str(format!("{}\r\n", "-".repeat(header.len())).as_str());
-        for event in &[
-            Event::FirstDaily,
-            Event::FirstNightly,
-            Event::SecondDaily,
-            Event::SecondNightly,
-        ] {
+        for event in &Event::ALL {
             s.push_str(format!("{}    |", event).as_str());
             for events in self.days.values() {
                 s.push_str(
@@ -128,6 +461,19 @@ mod tests {
     use super::*;
     use time::Date;
 
+    #[test]
+    fn test_serialize() {
+        let from = Date::from_ordinal_date(2025, 1).unwrap();
+        let to = Date::from_ordinal_date(2025, 2).unwrap();
+        let mut calendar = Calendar::new(from, to);
+        calendar.set_for(from, Event::FirstNightly, "Bob".to_string());
+        calendar.set_for(from, Event::FirstDaily, "Alice".to_string());
+        assert_eq!(
+            serde_json::to_string(&calendar).unwrap(),
+            r#"{"from":"2025-01-01","to":"2025-01-02","days":{"2025-01-01":{"FirstDaily":"Alice","FirstNightly":"Bob"},"2025-01-02":{}}}"#
+        );
+    }
+
     #[test]
     fn test_calendar_new() {
         let from = Date::from_ordinal_date(2025, 1).unwrap();
@@ -138,6 +484,17 @@ mod tests {
         assert_eq!(calendar.days.get(&to).unwrap().len(), 0);
     }
 
+    #[test]
+    fn test_new_across_years() {
+        let from = Date::from_calendar_date(2024, time::Month::December, 30).unwrap();
+        let to = Date::from_calendar_date(2025, time::Month::January, 3).unwrap();
+        let calendar = Calendar::new(from, to);
+        assert_eq!(calendar.get_all().len(), 5);
+        assert_eq!(calendar.get_all().keys().next(), Some(&from));
+        assert_eq!(calendar.get_all().keys().last(), Some(&to));
+        assert_eq!(calendar.get_empty_days(&Event::FirstDaily).len(), 5);
+    }
+
     #[test]
     fn test_get() {
         let from = Date::from_ordinal_date(2025, 1).unwrap();
@@ -147,6 +504,88 @@ mod tests {
         assert!(calendar.get_for(&from, &Event::FirstDaily).is_none());
     }
 
+    #[test]
+    fn test_days() {
+        let from = Date::from_ordinal_date(2025, 1).unwrap();
+        let to = Date::from_ordinal_date(2025, 10).unwrap();
+        let calendar = Calendar::new(from, to);
+        assert_eq!(calendar.day_count(), 10);
+        assert_eq!(calendar.days().next(), Some(from));
+        assert_eq!(calendar.days().last(), Some(to));
+        assert!(calendar.days().eq(calendar.get_all().keys().cloned()));
+    }
+
+    #[test]
+    fn test_events_on_day() {
+        let from = Date::from_ordinal_date(2025, 1).unwrap();
+        let to = Date::from_ordinal_date(2025, 10).unwrap();
+        let mut calendar = Calendar::new(from, to);
+        calendar.set_for(from, Event::FirstDaily, "Alice".to_string());
+        let events = calendar.events_on_day(&from).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events.get(&Event::FirstDaily), Some(&"Alice".to_string()));
+        assert!(calendar.events_on_day(&to).unwrap().is_empty());
+        assert!(calendar.events_on_day(&to.next_day().unwrap()).is_none());
+    }
+
+    #[cfg(feature = "compact")]
+    #[test]
+    fn test_serialize_compact() {
+        let from = Date::from_ordinal_date(2025, 1).unwrap();
+        let to = Date::from_ordinal_date(2025, 10).unwrap();
+        let mut calendar = Calendar::new(from, to);
+        calendar.set_for(from, Event::FirstDaily, "Alice".to_string());
+        calendar.set_for(from, Event::SecondNightly, "Bob".to_string());
+        calendar.set_for(to, Event::FirstNightly, "Élodie".to_string());
+
+        let data = calendar.serialize_compact().unwrap();
+        // 2 days of 6 bytes, then 3 events of 5 bytes plus the name
+        assert_eq!(data.len(), 2 * 6 + 3 * 5 + "AliceBobÉlodie".len());
+        let deserialized = Calendar::deserialize_compact(&data).unwrap();
+        assert_eq!((deserialized.from(), deserialized.to()), (from, to));
+        assert_eq!(deserialized.get_all(), calendar.get_all());
+
+        assert_eq!(
+            Calendar::deserialize_compact(&data[..data.len() - 1]).err(),
+            Some(AubepineError::InvalidCompact("Truncated name".to_string()))
+        );
+        let mut unknown_event = data.clone();
+        unknown_event[14] = 4;
+        assert_eq!(
+            Calendar::deserialize_compact(&unknown_event).err(),
+            Some(AubepineError::InvalidCompact("Unknown event 4".to_string()))
+        );
+
+        // The day before the last one can be represented, but not two days after it
+        let last_but_one = Date::MAX.previous_day().unwrap();
+        let mut overflowing = Calendar::new(last_but_one, last_but_one)
+            .serialize_compact()
+            .unwrap();
+        overflowing.extend([2, 0, 0, 0, 0]);
+        assert_eq!(
+            Calendar::deserialize_compact(&overflowing).err(),
+            Some(AubepineError::InvalidCompact("Day 2 too far".to_string()))
+        );
+
+        calendar.set_for(to, Event::SecondDaily, "A".repeat(u16::MAX as usize + 1));
+        assert!(matches!(
+            calendar.serialize_compact(),
+            Err(AubepineError::InvalidCompact(_))
+        ));
+    }
+
+    #[test]
+    fn test_event_predicates() {
+        assert!(Event::FirstDaily.is_daily() && Event::FirstDaily.is_first_level());
+        assert!(Event::FirstNightly.is_nightly() && Event::FirstNightly.is_first_level());
+        assert!(Event::SecondDaily.is_daily() && Event::SecondDaily.is_second_level());
+        assert!(Event::SecondNightly.is_nightly() && Event::SecondNightly.is_second_level());
+        assert!(!Event::FirstDaily.is_nightly() && !Event::FirstDaily.is_second_level());
+        assert!(!Event::SecondNightly.is_daily() && !Event::SecondNightly.is_first_level());
+        assert_eq!(Event::FirstNightly.level(), 1);
+        assert_eq!(Event::SecondDaily.level(), 2);
+    }
+
     #[test]
     fn test_get_missing() {
         let from = Date::from_ordinal_date(2025, 1).unwrap();
@@ -156,4 +595,122 @@ mod tests {
         calendar.set_for(from, Event::FirstDaily, "Alice".to_string());
         assert_eq!(calendar.get_empty_days(&Event::FirstDaily).len(), 9);
     }
+
+    #[test]
+    fn test_hours_in_week_for_person() {
+        // From Wednesday to Tuesday
+        let from = Date::from_ordinal_date(2025, 1).unwrap();
+        let to = Date::from_ordinal_date(2025, 7).unwrap();
+        let mut calendar = Calendar::new(from, to);
+        calendar.set_for(from, Event::FirstDaily, "Alice".to_string());
+        calendar.set_for(from, Event::SecondNightly, "Alice".to_string());
+        calendar.set_for(to, Event::FirstDaily, "Alice".to_string());
+        assert_eq!(calendar.hours_in_week_for_person("Alice", from), 24);
+        assert_eq!(calendar.hours_in_week_for_person("Alice", to), 12);
+        assert_eq!(calendar.hours_in_week_for_person("Bob", from), 0);
+    }
+
+    #[test]
+    fn test_weekly_rotation_report() {
+        let from = Date::from_ordinal_date(2025, 1).unwrap();
+        let to = Date::from_ordinal_date(2025, 7).unwrap();
+        let mut calendar = Calendar::new(from, to);
+        for (i, name) in ["A", "B", "C", "A", "B", "C", "A"].iter().enumerate() {
+            let day = from + time::Duration::days(i as i64);
+            calendar.set_for(day, Event::FirstDaily, name.to_string());
+        }
+        let analysis = calendar.weekly_rotation_report(Event::FirstDaily);
+        assert!(analysis.is_round_robin);
+        assert_eq!(analysis.rotation_period_days, Some(3));
+        assert!(analysis.deviations.is_empty());
+
+        calendar.set_for(to, Event::FirstDaily, "B".to_string());
+        let analysis = calendar.weekly_rotation_report(Event::FirstDaily);
+        assert!(!analysis.is_round_robin);
+        assert_eq!(analysis.rotation_period_days, Some(3));
+        assert_eq!(
+            analysis.deviations,
+            vec![(to, "A".to_string(), "B".to_string())]
+        );
+
+        let analysis = calendar.weekly_rotation_report(Event::SecondDaily);
+        assert!(!analysis.is_round_robin);
+        assert_eq!(analysis.rotation_period_days, None);
+    }
+
+    #[test]
+    fn test_busiest_and_least_busy_person() {
+        let from = Date::from_ordinal_date(2025, 1).unwrap();
+        let to = Date::from_ordinal_date(2025, 3).unwrap();
+        let mut calendar = Calendar::new(from, to);
+        assert_eq!(calendar.busiest_person(), None);
+        calendar.set_for(from, Event::FirstDaily, "Bob".to_string());
+        calendar.set_for(from, Event::FirstNightly, "Alice".to_string());
+        calendar.set_for(to, Event::FirstDaily, "Charlie".to_string());
+        calendar.set_for(to, Event::FirstNightly, "Bob".to_string());
+        assert_eq!(calendar.busiest_person(), Some((&"Bob".to_string(), 2)));
+        assert_eq!(
+            calendar.least_busy_person(),
+            Some((&"Alice".to_string(), 1))
+        );
+    }
+
+    #[test]
+    fn test_anonymize() {
+        let from = Date::from_ordinal_date(2025, 1).unwrap();
+        let to = Date::from_ordinal_date(2025, 3).unwrap();
+        let mut calendar = Calendar::new(from, to);
+        calendar.set_for(from, Event::FirstNightly, "Bob".to_string());
+        calendar.set_for(from, Event::FirstDaily, "Charlie".to_string());
+        calendar.set_for(to, Event::FirstDaily, "Alice".to_string());
+        calendar.set_for(to, Event::FirstNightly, "Bob".to_string());
+        let (anonymized, names) = calendar.anonymize();
+        assert_eq!(
+            anonymized.get_for(&from, &Event::FirstDaily),
+            Some(&"P01".to_string())
+        );
+        assert_eq!(
+            anonymized.get_for(&from, &Event::FirstNightly),
+            Some(&"P02".to_string())
+        );
+        assert_eq!(
+            anonymized.get_for(&to, &Event::FirstDaily),
+            Some(&"P03".to_string())
+        );
+        assert_eq!(
+            anonymized.get_for(&to, &Event::FirstNightly),
+            Some(&"P02".to_string())
+        );
+        assert_eq!(anonymized.get_empty_days(&Event::SecondDaily).len(), 3);
+        assert_eq!(names.len(), 3);
+        assert_eq!(names["P02"], "Bob");
+        for (day, on_call) in anonymized.get_all() {
+            for (event, code) in on_call {
+                assert_eq!(calendar.get_for(day, event), Some(&names[code]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_labels() {
+        assert_eq!(Event::SecondNightly.to_label(Locale::Fr), "2ème SF nuit");
+        assert_eq!(Event::SecondNightly.to_label(Locale::En), "2nd nightly");
+        assert_eq!(Event::from_label("1ère SF nuit"), Some(Event::FirstNightly));
+        assert_eq!(Event::from_label("1st nightly"), Some(Event::FirstNightly));
+        assert_eq!(Event::from_label("SF 1"), None);
+    }
+
+    #[test]
+    fn test_assignments_for_person() {
+        let from = Date::from_ordinal_date(2025, 1).unwrap();
+        let to = Date::from_ordinal_date(2025, 10).unwrap();
+        let mut calendar = Calendar::new(from, to);
+        calendar.set_for(to, Event::FirstDaily, "Alice".to_string());
+        calendar.set_for(from, Event::SecondNightly, "Alice".to_string());
+        calendar.set_for(from, Event::FirstDaily, "Bob".to_string());
+        assert_eq!(
+            calendar.assignments_for_person("Alice"),
+            vec![(from, Event::SecondNightly), (to, Event::FirstDaily)]
+        );
+    }
 }
@@ -0,0 +1,189 @@
+use std::fmt;
+use std::num::ParseIntError;
+
+use itertools::Itertools;
+use time::Date;
+
+use crate::calendar::{Event, Locale};
+use crate::Name;
+
+/// Error returned when the calendar can't be made.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// Some days / events are still empty, even with the maximum number of subcontractors.
+    Unfilled(Vec<(Date, Event)>),
+    /// More days / events have nobody available than subcontractors allowed, so the search isn't even run.
+    Infeasible(Vec<(Date, Event)>),
+    /// The solver didn't run to completion, e.g. because it panicked in a background task.
+    Aborted,
+    /// None of the days to schedule is within the calendar.
+    OutOfRange,
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let slots = |slots: &[(Date, Event)]| {
+            slots
+                .iter()
+                .map(|(day, event)| format!("{} {}", day, event.to_label(Locale::default())))
+                .join(", ")
+        };
+        match self {
+            ScheduleError::Unfilled(empty_slots) => write!(
+                f,
+                "No calendar found, {} day / event left empty: {}",
+                empty_slots.len(),
+                slots(empty_slots)
+            ),
+            ScheduleError::Infeasible(cells) => write!(
+                f,
+                "Nobody is available for {} day / event, more than the subcontractors allowed: {}",
+                cells.len(),
+                slots(cells)
+            ),
+            ScheduleError::Aborted => write!(f, "The solver didn't run to completion"),
+            ScheduleError::OutOfRange => {
+                write!(f, "None of the days to schedule is within the calendar")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+/// Error returned when the input is invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AubepineError {
+    /// The CSV file can't be read.
+    InvalidCsv(String),
+    /// The JSON configuration can't be parsed.
+    InvalidJson(String),
+    /// The iCalendar content can't be parsed.
+    InvalidICal(String),
+    /// The compact binary serialization of a calendar can't be read, or written.
+    InvalidCompact(String),
+    /// The on-call level of an availability row is unknown, with the line of the CSV file if known.
+    UnknownLevel(String, Option<usize>),
+    /// A number of the CSV file, e.g. the year or a day of the header, can't be parsed: the line, the token and why.
+    InvalidNumber(usize, String, ParseIntError),
+    /// The membership of a person is unknown.
+    UnknownMembership(String),
+    /// There's no person with this name.
+    UnknownPerson(Name),
+    /// The first day of the range is after the last one.
+    InvalidRange(Date, Date),
+    /// The year is out of the range of the supported dates.
+    InvalidYear(i32),
+    /// The day isn't within the calendar.
+    DateOutOfRange(Date),
+    /// The person isn't available for this day and event.
+    NotAvailable(Name, Date, Event),
+    /// The person isn't on-call for this day and event.
+    NotOnCall(Name, Date, Event),
+}
+
+impl fmt::Display for AubepineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AubepineError::InvalidCsv(reason) => write!(f, "Invalid CSV file: {}", reason),
+            AubepineError::InvalidJson(reason) => {
+                write!(f, "Invalid JSON configuration: {}", reason)
+            }
+            AubepineError::InvalidICal(reason) => write!(f, "Invalid iCalendar: {}", reason),
+            AubepineError::InvalidCompact(reason) => {
+                write!(f, "Invalid compact calendar: {}", reason)
+            }
+            AubepineError::UnknownLevel(level, line) => {
+                let expected = Event::ALL
+                    .map(|event| format!("'{}'", event.to_label(Locale::default())))
+                    .join(", ");
+                write!(f, "Unknown on-call level '{}'", level)?;
+                if let Some(line) = line {
+                    write!(f, " at line {}", line)?;
+                }
+                write!(f, " (expected one of: {})", expected)
+            }
+            AubepineError::InvalidNumber(line, token, error) => {
+                write!(f, "Invalid number '{}' at line {}: {}", token, line, error)
+            }
+            AubepineError::UnknownMembership(membership) => write!(
+                f,
+                "Unknown membership '{}' (expected one of: 'Employee', 'Subcontractor', \
+                'Salarié', 'Sous-traitant')",
+                membership
+            ),
+            AubepineError::UnknownPerson(name) => write!(f, "Unknown person '{}'", name),
+            AubepineError::InvalidRange(from, to) => {
+                write!(f, "Invalid range: {} is after {}", from, to)
+            }
+            AubepineError::InvalidYear(year) => write!(f, "Invalid year: {}", year),
+            AubepineError::DateOutOfRange(day) => write!(f, "{} is not within the calendar", day),
+            AubepineError::NotAvailable(name, day, event) => write!(
+                f,
+                "{} is not available on {} for {}",
+                name,
+                day,
+                event.to_label(Locale::default())
+            ),
+            AubepineError::NotOnCall(name, day, event) => write!(
+                f,
+                "{} is not on-call on {} for {}",
+                name,
+                day,
+                event.to_label(Locale::default())
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AubepineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AubepineError::InvalidNumber(_, _, error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let day = Date::from_ordinal_date(2025, 1).unwrap();
+        assert_eq!(
+            AubepineError::UnknownLevel("SF 1".to_string(), Some(4)).to_string(),
+            "Unknown on-call level 'SF 1' at line 4 (expected one of: '1ère SF jour', '1ère SF nuit', '2ème SF jour', '2ème SF nuit')"
+        );
+        assert!(AubepineError::UnknownLevel("SF 1".to_string(), None)
+            .to_string()
+            .starts_with("Unknown on-call level 'SF 1' (expected"));
+        assert_eq!(
+            AubepineError::NotAvailable("Alice".to_string(), day, Event::FirstNightly).to_string(),
+            "Alice is not available on 2025-01-01 for 1ère SF nuit"
+        );
+        let error: Box<dyn std::error::Error> = Box::new(AubepineError::DateOutOfRange(day));
+        assert_eq!(error.to_string(), "2025-01-01 is not within the calendar");
+        assert!(error.source().is_none());
+
+        let parse_error = "2O25".parse::<i32>().unwrap_err();
+        let error: Box<dyn std::error::Error> = Box::new(AubepineError::InvalidNumber(
+            1,
+            "2O25".to_string(),
+            parse_error.clone(),
+        ));
+        assert_eq!(
+            error.to_string(),
+            "Invalid number '2O25' at line 1: invalid digit found in string"
+        );
+        assert_eq!(error.source().unwrap().to_string(), parse_error.to_string());
+
+        let error: Box<dyn std::error::Error> =
+            Box::new(ScheduleError::Unfilled(vec![(day, Event::FirstDaily)]));
+        assert_eq!(
+            error.to_string(),
+            "No calendar found, 1 day / event left empty: 2025-01-01 1ère SF jour"
+        );
+    }
+}
@@ -0,0 +1,77 @@
+//! Minimal iCalendar (RFC 5545) support, to share the on-call calendar with the persons.
+//! Daily events start at 08:00 and nightly events at 20:00.
+
+use time::{Date, Time};
+
+use crate::calendar::{Event, Locale};
+
+const PRODID: &str = "-//aubepine//on-call calendar//FR";
+pub(crate) const DAILY_START_HOUR: u8 = 8;
+pub(crate) const NIGHTLY_START_HOUR: u8 = 20;
+
+/// Build a VCALENDAR containing one VEVENT per day and event the person designated by `name` is on-call.
+pub fn person_calendar(name: &str, assignments: &[(Date, Event)]) -> String {
+    let mut ical = String::new();
+    ical.push_str("BEGIN:VCALENDAR\r\n");
+    ical.push_str("VERSION:2.0\r\n");
+    ical.push_str(&format!("PRODID:{}\r\n", PRODID));
+    for (day, event) in assignments {
+        let start_hour = match event {
+            Event::FirstDaily | Event::SecondDaily => DAILY_START_HOUR,
+            Event::FirstNightly | Event::SecondNightly => NIGHTLY_START_HOUR,
+        };
+        let start = day.with_time(Time::from_hms(start_hour, 0, 0).unwrap());
+        let end = start + time::Duration::hours(event.duration_hours() as i64);
+        let summary = event.to_label(Locale::default());
+        ical.push_str("BEGIN:VEVENT\r\n");
+        ical.push_str(&format!(
+            "UID:{}-{}-{}@aubepine\r\n",
+            format_date(*day),
+            event,
+            name
+        ));
+        // Use the start of the event as timestamp, so that the export is reproducible
+        ical.push_str(&format!("DTSTAMP:{}\r\n", format_date_time(start)));
+        ical.push_str(&format!("DTSTART:{}\r\n", format_date_time(start)));
+        ical.push_str(&format!("DTEND:{}\r\n", format_date_time(end)));
+        ical.push_str(&format!("SUMMARY:{} - {}\r\n", summary, name));
+        ical.push_str("END:VEVENT\r\n");
+    }
+    ical.push_str("END:VCALENDAR\r\n");
+    ical
+}
+
+fn format_date(day: Date) -> String {
+    format!("{:04}{:02}{:02}", day.year(), day.month() as u8, day.day())
+}
+
+fn format_date_time(date_time: time::PrimitiveDateTime) -> String {
+    format!(
+        "{}T{:02}{:02}{:02}",
+        format_date(date_time.date()),
+        date_time.hour(),
+        date_time.minute(),
+        date_time.second()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_person_calendar() {
+        let day = Date::from_ordinal_date(2025, 1).unwrap();
+        let ical = person_calendar(
+            "Alice",
+            &[(day, Event::FirstDaily), (day, Event::SecondNightly)],
+        );
+        assert!(ical.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ical.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ical.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ical.contains("DTSTART:20250101T080000\r\nDTEND:20250101T200000\r\n"));
+        // Nightly events end the next day
+        assert!(ical.contains("DTSTART:20250101T200000\r\nDTEND:20250102T080000\r\n"));
+        assert!(ical.contains("SUMMARY:2ème SF nuit - Alice\r\n"));
+    }
+}
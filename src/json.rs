@@ -0,0 +1,131 @@
+//! JSON alternative to the CSV file, for the integrations producing the availabilities as JSON.
+//! See `CalendarMaker::from_json_config` for the schema.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Deserialize;
+use time::Date;
+
+use crate::availabilities::Availabilities;
+use crate::calendar::{Calendar, Event, Locale};
+use crate::error::AubepineError;
+use crate::person::{Membership, Person};
+use crate::utils::{parse_iso_date, DateExt};
+use crate::{AvailabilitiesPerPerson, Name};
+
+#[derive(Deserialize)]
+struct Config {
+    from: String,
+    to: String,
+    persons: Vec<PersonConfig>,
+}
+
+#[derive(Deserialize)]
+struct PersonConfig {
+    name: Name,
+    membership: Option<String>,
+    availability: BTreeMap<String, Vec<Event>>,
+}
+
+/// Parse the JSON configuration into the calendar, the availabilities and the persons.
+pub fn parse_config(
+    json: &str,
+) -> Result<(Calendar, AvailabilitiesPerPerson, HashMap<Name, Person>), AubepineError> {
+    let config: Config = serde_json::from_str(json)
+        .map_err(|error| AubepineError::InvalidJson(error.to_string()))?;
+    let from = parse_date(&config.from)?;
+    let to = parse_date(&config.to)?;
+    if from > to {
+        return Err(AubepineError::InvalidRange(from, to));
+    }
+    let calendar = Calendar::new(from, to);
+    let mut availabilities = AvailabilitiesPerPerson::new();
+    let mut persons = HashMap::new();
+    for person_config in config.persons {
+        let mut available_days = HashMap::new();
+        for (day, events) in &person_config.availability {
+            let day = parse_date(day)?;
+            if !day.is_in_range(from, to) {
+                return Err(AubepineError::DateOutOfRange(day));
+            }
+            available_days.insert(day, events);
+        }
+        // Same rows as in the CSV file, one per event
+        let mut her_availabilities = Availabilities::from_ical("")?;
+        for event in Event::ALL {
+            let mut line = event.to_label(Locale::default()).to_string();
+            for day in calendar.days() {
+                let is_available = available_days
+                    .get(&day)
+                    .is_some_and(|events| events.contains(&event));
+                line.push_str(if is_available { "," } else { ",x" });
+            }
+            her_availabilities.merge(from, to, &line);
+        }
+        let membership = match &person_config.membership {
+            Some(membership) => membership.parse()?,
+            None => Membership::default(),
+        };
+        let person = match membership {
+            Membership::Employee => Person::new_employee(&person_config.name),
+            Membership::Subcontractor => Person::new_subcontractor(&person_config.name),
+        };
+        availabilities.insert(person_config.name.clone(), her_availabilities);
+        persons.insert(person_config.name, person);
+    }
+    Ok((calendar, availabilities, persons))
+}
+
+fn parse_date(s: &str) -> Result<Date, AubepineError> {
+    parse_iso_date(s).ok_or_else(|| AubepineError::InvalidJson(format!("invalid date '{}'", s)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let json = r#"{
+            "from": "2025-05-05",
+            "to": "2025-05-07",
+            "persons": [
+                { "name": "Alice", "availability": { "2025-05-05": ["FirstDaily", "FirstNightly"] } },
+                { "name": "EXT-0", "membership": "Sous-traitant", "availability": { "2025-05-07": ["SecondNightly"] } }
+            ]
+        }"#;
+        let (calendar, availabilities, persons) = parse_config(json).unwrap();
+        assert_eq!(calendar.day_count(), 3);
+        let may_5 = parse_iso_date("2025-05-05").unwrap();
+        let may_6 = parse_iso_date("2025-05-06").unwrap();
+        assert_eq!(
+            availabilities["Alice"].get(&may_5),
+            Some(&vec![Event::FirstDaily, Event::FirstNightly])
+        );
+        assert_eq!(availabilities["Alice"].get(&may_6), Some(&vec![]));
+        assert!(persons["EXT-0"].is_subcontractor());
+        assert!(!persons["Alice"].is_subcontractor());
+    }
+
+    #[test]
+    fn test_parse_config_errors() {
+        let config = |availability: &str| {
+            format!(
+                r#"{{ "from": "2025-05-05", "to": "2025-05-07", "persons": [{{ "name": "Alice", "availability": {} }}] }}"#,
+                availability
+            )
+        };
+        assert!(matches!(
+            parse_config(&config(r#"{ "2025-05-08": ["FirstDaily"] }"#)),
+            Err(AubepineError::DateOutOfRange(_))
+        ));
+        assert!(matches!(
+            parse_config(&config(r#"{ "2025-05-05": ["FirstWeekly"] }"#)),
+            Err(AubepineError::InvalidJson(_))
+        ));
+        assert!(matches!(
+            parse_config("{}"),
+            Err(AubepineError::InvalidJson(_))
+        ));
+    }
+}
@@ -1,41 +1,190 @@
+use std::cell::RefCell;
 use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::Arc;
 
-use availabilities::Availabilities;
-use calendar::{Calendar, Event};
+pub use availabilities::Availabilities;
+use calendar::DAILY_DURATION_HOURS;
+pub use calendar::{Calendar, Event, Locale, RotationAnalysis};
+pub use error::{AubepineError, ScheduleError};
 use itertools::Itertools;
+pub use person::{ContractType, Membership, Person, Seniority};
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+pub use report::{
+    CoverageReport, EventCoverage, ParetoPoint, PartialSolveResult, ScheduleReport, SearchProgress,
+    SearchStats, SimulationResult, SubcoExplanation,
+};
+pub use strategy::{BalancingStrategy, FirstAssignmentStrategy, TieBreakingStrategy};
 use time::Date;
+pub use utils::parse_iso_date;
+use utils::DateExt;
 
 mod availabilities;
 mod calendar;
+mod error;
+mod ical;
+mod json;
+mod person;
+mod report;
+mod strategy;
+mod utils;
+#[cfg(feature = "wasm")]
+mod wasm;
 
 type Name = String;
 type AvailabilitiesPerPerson = HashMap<Name, Availabilities>;
 type ProblematicDays = BTreeMap<(Date, Event), u8>;
+/// The events each person is qualified for, see `CalendarMaker::set_qualified_events`
+pub type RoleMatrix = HashMap<Name, Vec<Event>>;
 
-#[derive(Debug)]
+/// Below this fraction of available days for an event, a person will likely force a subcontractor.
+const LOW_COVERAGE_DENSITY: f64 = 0.1;
+
+/// Prefix of the names of the subcontractors added by the solver.
+const SUBCONTRACTOR_PREFIX: &str = "EXT-";
+
+/// Callback of `CalendarMaker::set_on_progress`, shared by the clones of the calendar maker.
+#[derive(Clone)]
+struct ProgressCallback(Arc<dyn Fn(SearchProgress) + Send + Sync>);
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ProgressCallback")
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "wasm", wasm_bindgen::prelude::wasm_bindgen)]
 pub struct CalendarMaker {
     calendar: Calendar,
     availabilities: AvailabilitiesPerPerson,
+    /// Availabilities as parsed, before any allocation, including the subcontractors added so far
+    initial_availabilities: AvailabilitiesPerPerson,
+    persons: HashMap<Name, Person>,
+    min_seniority_per_event: HashMap<Event, Seniority>,
+    qualified_events: RoleMatrix,
+    on_call_chains: Vec<(Name, Name, Event)>,
+    /// Warnings raised while parsing the input
+    warnings: Vec<String>,
     problematic_days: ProblematicDays,
+    /// Why each subcontractor of the last call to `make_calendar` was added, in order
+    subco_explanations: Vec<SubcoExplanation>,
     max_subcontractor: u8,
     verbose: bool,
+    /// Whether a solver has been run, e.g. `make_calendar`, to tell an empty calendar from an unsolved one
+    calendar_made: bool,
+    balancing_strategy: BalancingStrategy,
+    /// On-call days of each person in the previous calendars, see `import_balance`
+    imported_balance: HashMap<Name, usize>,
+    tie_breaking_strategy: TieBreakingStrategy,
+    first_assignment_strategy: FirstAssignmentStrategy,
+    /// Whether the persons available for the fewest days / events are tried first, see `worst_case_solve`
+    scarce_persons_first: bool,
+    /// Whether the employees are tried before the subcontractors, see `solve_prioritizing_employees_over_subcontractors`
+    employees_first: bool,
+    /// Events sharing the adjacent days rule, see `solve_with_event_groups`
+    event_groups: Vec<Vec<Event>>,
+    /// Number of days without on-call above which a person is tried first, see `solve_with_max_gap`
+    max_gap: Option<u8>,
+    /// Minimum number of hours between the end of a nightly event and the next event, see
+    /// `solve_with_day_off_after_night`
+    rest_hours_after_night: u16,
+    /// Maximum number of on-call hours per ISO week for everybody, see `solve_with_max_weekly_oncall_hours`
+    max_weekly_oncall_hours: Option<u16>,
+    /// Public holidays followed by two days of rest, see `solve_with_holiday_bonus_days`
+    holidays: Vec<Date>,
+    /// Whether the daily and nightly events of a level and a day go to two persons, see
+    /// `solve_avoiding_same_person_for_day_and_night`
+    avoid_same_day_double: bool,
+    /// Whether a senior person is tried first for the nights without any senior, see `solve_with_senior_night_pairing`
+    senior_night_pairing: bool,
+    seed: Option<u64>,
+    /// Shared by the random strategies once a seed is set, hence mutable while solving
+    rng: RefCell<SmallRng>,
+    on_progress: Option<ProgressCallback>,
 }
 
 impl CalendarMaker {
     /// First row contains the month, the year and the days of the week, separated by commas.
     /// The following rows contain the name of the person and the availabilities for each day, each separated by a comma.
     pub fn from_file(filename: &str) -> Self {
-        let mut calendar_maker;
+        Self::try_from_file(filename).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Same as `from_file`, but return an error when the file can't be read, isn't encoded in UTF-8, or doesn't
+    /// start with a known month.
+    pub fn try_from_file(filename: &str) -> Result<Self, AubepineError> {
+        let bytes = std::fs::read(filename)
+            .map_err(|error| AubepineError::InvalidCsv(format!("{}: {}", filename, error)))?;
+        if bytes.starts_with(&[0xFF, 0xFE]) || bytes.starts_with(&[0xFE, 0xFF]) {
+            return Err(AubepineError::InvalidCsv(format!(
+                "{} is encoded in UTF-16, save it as UTF-8",
+                filename
+            )));
+        }
+        let content = String::from_utf8(bytes).map_err(|_| {
+            AubepineError::InvalidCsv(format!("{} is not encoded in UTF-8", filename))
+        })?;
+        let content = content.strip_prefix("\u{feff}").unwrap_or(&content);
+        let month = content.split([',', ';']).next().unwrap_or_default();
+        if month_from_label(month).is_none() {
+            return Err(AubepineError::InvalidCsv(format!(
+                "{} doesn't start with a month, but with '{}'",
+                filename, month
+            )));
+        }
+        Self::check_csv(content)?;
+        Ok(Self::from_csv_string(content))
+    }
+
+    /// Return the first error of the header or of the on-call levels of the CSV content, with its line number.
+    fn check_csv(content: &str) -> Result<(), AubepineError> {
+        let mut lines = content.lines();
+        Self::parse_header(lines.next().unwrap_or_default())?;
+        for (i, line) in lines.enumerate() {
+            // The header is the first line
+            let line_number = i + 2;
+            let Some((_, availabilities_str)) = line.split_once([',', ';']) else {
+                return Err(AubepineError::InvalidCsv(format!(
+                    "Name missing at line {}",
+                    line_number
+                )));
+            };
+            availabilities::try_extract_availability_info(availabilities_str).map_err(|error| {
+                match error {
+                    AubepineError::UnknownLevel(level, _) => {
+                        AubepineError::UnknownLevel(level, Some(line_number))
+                    }
+                    error => error,
+                }
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Same as `from_file`, but taking the content of the CSV file instead of its path.
+    pub fn from_csv_string(content: &str) -> Self {
+        let content = content.strip_prefix("\u{feff}").unwrap_or(content);
         // Use first row to build the calendar
-        let file_content = std::fs::read_to_string(filename).expect("Could not read file");
-        let file_content = file_content
-            .strip_prefix("\u{feff}")
-            .unwrap_or(&file_content);
-        calendar_maker = Self::from_lines(&mut file_content.lines());
-        calendar_maker.take_initial_allocations(file_content.lines());
+        let mut calendar_maker = Self::from_lines(&mut content.lines());
+        calendar_maker.take_initial_allocations(content.lines());
         calendar_maker
     }
 
+    /// Same as `from_file`, but taking a JSON configuration instead of a CSV file, such as
+    /// `{ "from": "2025-05-05", "to": "2025-05-20", "persons": [{ "name": "Alice", "membership": "Employee",
+    /// "availability": { "2025-05-05": ["FirstDaily", "FirstNightly"] } }] }`.
+    /// The persons are unavailable on the days missing from `availability`, and the membership is optional.
+    /// Nobody is on-call yet.
+    pub fn from_json_config(json: &str) -> Result<Self, AubepineError> {
+        let (calendar, availabilities, persons) = json::parse_config(json)?;
+        let mut calendar_maker = Self::new(calendar, availabilities);
+        calendar_maker.persons = persons;
+        Ok(calendar_maker)
+    }
+
     /// Fill the calendar, in order to have one person per day and per event. To find who can be on-call, use the availabilities of each person.
     /// The rules are the following:
     ///  - One person can't be on-call for two consecutive days, except for the Second level on friday, saturday and sunday.
@@ -45,18 +194,96 @@ impl CalendarMaker {
     /// When finding a person for a day, remove them from the list of available persons for this day, but also the previous and the next day.
     /// Try all the possibilities, recursively, stopping when all the days are filled.
     /// Try first without adding extra ressources, then add one subcontractor, then two, etc. up to the maximum number of subcontractors passed as argument.
+    /// If more days / events than that have nobody available at all, the calendar is left untouched without searching.
+    /// The calendar is also left untouched when no solution is found, see `try_make_calendar` to know why.
     pub fn make_calendar(&mut self, max_subcontractor: u8, verbose: bool) {
+        if let Err(ScheduleError::Infeasible(infeasible_cells)) =
+            self.try_make_calendar(max_subcontractor, verbose)
+        {
+            eprintln!(
+                "Nobody is available for {} day(s) / event(s), but only {} subcontractor(s) allowed",
+                infeasible_cells.len(),
+                max_subcontractor
+            );
+        }
+    }
+
+    /// Same as `make_calendar`, but return the days / events nobody is available for when there are more of them than
+    /// `max_subcontractor`, or the days / events left empty by the best partial calendar when no solution is found.
+    pub fn try_make_calendar(
+        &mut self,
+        max_subcontractor: u8,
+        verbose: bool,
+    ) -> Result<(), ScheduleError> {
         self.max_subcontractor = max_subcontractor;
         self.verbose = verbose;
-        for i in 0..=max_subcontractor {
+        self.calendar_made = true;
+        let infeasible_cells = self.report_infeasible_cells();
+        if infeasible_cells.len() > max_subcontractor as usize {
+            return Err(ScheduleError::Infeasible(infeasible_cells));
+        }
+        // The best partial calendar is dropped, the calendar is left untouched when no solution is found
+        self.solve()
+            .map_err(|best_partial| ScheduleError::Unfilled(Self::empty_slots_of(&best_partial)))
+    }
+
+    /// Same as `make_calendar`, but among the days with the least available persons, start with the days of the
+    /// person available for the fewest days / events, and try her first. Using the scarcest persons early may find a
+    /// solution faster when the availabilities are tight.
+    pub fn worst_case_solve(&mut self, max_subco: u8) {
+        self.scarce_persons_first = true;
+        self.make_calendar(max_subco, self.verbose);
+        self.scarce_persons_first = false;
+    }
+
+    /// Same as `make_calendar`, but with the `LeastOverall` balancing strategy, an employee is tried before a
+    /// subcontractor on-call once more than her, or less.
+    pub fn solve_prioritizing_employees_over_subcontractors(&mut self, max_subco: u8) {
+        self.employees_first = true;
+        self.make_calendar(max_subco, self.verbose);
+        self.employees_first = false;
+    }
+
+    /// Same as `make_calendar`, but when no solution is found, return the calendar with the most days / events
+    /// allocated, and what's missing. The search is run even if some days / events have nobody available.
+    /// The calendar maker is only updated when a solution is found.
+    pub fn solve_with_partial_result(&mut self, max_subco: u8) -> PartialSolveResult {
+        self.max_subcontractor = max_subco;
+        let person_count = self.person_count();
+        let calendar = match self.solve() {
+            Ok(()) => self.calendar.clone(),
+            Err(best_partial) => best_partial,
+        };
+        let unfilled = Self::empty_slots_of(&calendar);
+        PartialSolveResult {
+            calendar,
+            unfilled,
+            subcontractors_added: (self.person_count() - person_count) as u8,
+        }
+    }
+
+    /// Run the solver with up to `max_subcontractor` subcontractors. On failure, return the best partial calendar
+    /// found with the most subcontractors.
+    fn solve(&mut self) -> Result<(), Calendar> {
+        self.calendar_made = true;
+        // The initial allocations may already break the rules, only the new violations are a bug of the solver
+        let violations_before = if cfg!(debug_assertions) {
+            self.check_consecutive_day_rule_violations()
+        } else {
+            Vec::new()
+        };
+        let mut best_partial = self.calendar.clone();
+        self.subco_explanations.clear();
+        for i in 0..=self.max_subcontractor {
             if self.verbose {
                 println!("Trying with {} subcontractor(s)", i);
             }
             match self.try_all_permutations() {
-                Err(problematic_days) => {
+                Err((problematic_days, partial)) => {
+                    best_partial = partial;
                     if let Some(most_problematic_day) = problematic_days.iter().max_by_key(|e| e.1)
                     {
-                        println!(
+                        eprintln!(
                             "Most problematic day / event : {:?} / {:?} ({})",
                             most_problematic_day.0 .0,
                             most_problematic_day.0 .1,
@@ -64,446 +291,4702 @@ impl CalendarMaker {
                         );
                     }
                     self.problematic_days = problematic_days.clone();
-                    let most_problematic_day_and_event =
-                        problematic_days.iter().max_by_key(|e| e.1).unwrap().0;
-                    let subco_name = format!("EXT-{}", i);
-                    let new_availabilities = self.add_subco_for_this_day_and_event(
-                        &self.availabilities.clone(),
-                        &subco_name,
-                        most_problematic_day_and_event.0.ordinal(),
-                        most_problematic_day_and_event.1,
-                    );
-                    self.availabilities = new_availabilities;
-                    continue;
+                    if i == self.max_subcontractor {
+                        break;
+                    }
+                    let &(day, event) = problematic_days.iter().max_by_key(|e| e.1).unwrap().0;
+                    self.call_subco(i, day, event, &best_partial);
                 }
                 Ok((cal, av)) => {
                     self.calendar = cal;
                     self.availabilities = av;
-                    break;
+                    debug_assert!(
+                        self.check_consecutive_day_rule_violations()
+                            .iter()
+                            .all(|violation| violations_before.contains(violation)),
+                        "The solver broke the consecutive days rule"
+                    );
+                    return Ok(());
                 }
             }
         }
+        Err(best_partial)
     }
 
-    fn take_initial_allocations(&mut self, lines: std::str::Lines) {
-        // Skip the first line, it's the header
-        let lines = lines.skip(1);
-        for line in lines {
-            let (name, availabilities_str) = line.split_once([',', ';']).expect("Name missing");
-            let on_call_allocations =
-                Availabilities::parse_initial_allocations(self.calendar.from(), availabilities_str);
-            for (day, event) in on_call_allocations {
-                self.calendar.set_for(day, event, name.to_string());
-                let her_availabilities = self.availabilities.get_mut(name).unwrap();
+    /// Same as `make_calendar`, but the persons who would otherwise stay more than `max_gap` days in a row without
+    /// on-call are tried first. It's only a preference: a small team may leave no choice. Return the gaps still too
+    /// long, see `check_max_gap`.
+    pub fn solve_with_max_gap(&mut self, max_gap: u8, max_subco: u8) -> Vec<(Name, Date, Date)> {
+        self.max_gap = Some(max_gap);
+        self.make_calendar(max_subco, self.verbose);
+        self.max_gap = None;
+        self.check_max_gap(max_gap)
+    }
+
+    /// Same as `make_calendar`, but nobody is on-call less than `rest_hours` after the end of a nightly event, at
+    /// 08:00. The next day, the daily events start right away, and the nightly events 12 hours later. It matters for
+    /// the Second level on friday, saturday and sunday, which can otherwise follow each other.
+    pub fn solve_with_day_off_after_night(&mut self, rest_hours: u16, max_subco: u8) {
+        self.rest_hours_after_night = rest_hours;
+        self.make_calendar(max_subco, self.verbose);
+        self.rest_hours_after_night = 0;
+    }
+
+    /// Same as `make_calendar`, but nobody is on-call for more than `max_hours` hours in an ISO week, e.g. 48 hours to
+    /// comply with the EU Working Time Directive. The maximum of each person, see `Person::set_max_hours_per_week`,
+    /// still applies when lower.
+    pub fn solve_with_max_weekly_oncall_hours(&mut self, max_hours: u16, max_subco: u8) {
+        self.max_weekly_oncall_hours = Some(max_hours);
+        self.make_calendar(max_subco, self.verbose);
+        self.max_weekly_oncall_hours = None;
+    }
+
+    /// Same as `make_calendar`, but a person on-call on a public holiday gets a compensatory rest day: she isn't
+    /// on-call the two following days, whatever the event.
+    pub fn solve_with_holiday_bonus_days(&mut self, holidays: &[Date], max_subco: u8) {
+        self.holidays = holidays.to_vec();
+        self.make_calendar(max_subco, self.verbose);
+        self.holidays.clear();
+    }
+
+    /// Same as `make_calendar`, but nobody is on-call for both the daily and the nightly events of a level on the same
+    /// day, even for the Second level on friday, saturday and sunday.
+    pub fn solve_avoiding_same_person_for_day_and_night(&mut self, max_subco: u8) {
+        self.avoid_same_day_double = true;
+        self.make_calendar(max_subco, self.verbose);
+        self.avoid_same_day_double = false;
+    }
+
+    /// Same as `make_calendar`, but for a nightly event of a day without any senior person on-call yet, the senior
+    /// persons are tried first. It's only a preference: return the days with a nightly event but without any senior
+    /// person on-call, see `check_senior_night_pairing`.
+    pub fn solve_with_senior_night_pairing(&mut self, max_subco: u8) -> Vec<Date> {
+        self.senior_night_pairing = true;
+        self.make_calendar(max_subco, self.verbose);
+        self.senior_night_pairing = false;
+        self.check_senior_night_pairing()
+    }
+
+    /// Return the days with a nightly event allocated, but without any person at least senior on-call, sorted.
+    pub fn check_senior_night_pairing(&self) -> Vec<Date> {
+        self.calendar
+            .get_all()
+            .iter()
+            .filter(|(_, events)| events.keys().any(Event::is_nightly))
+            .filter(|(_, events)| !events.values().any(|name| self.is_senior(name)))
+            .map(|(day, _)| *day)
+            .collect()
+    }
+
+    /// Return true if the person designated by `name` is at least senior.
+    fn is_senior(&self, name: &Name) -> bool {
+        self.persons
+            .get(name)
+            .is_some_and(|person| person.seniority() >= Seniority::Senior)
+    }
+
+    /// Same as `make_calendar`, but the empty days of `event` are first given in turn to the persons of `rotation`.
+    /// When the next person isn't available, qualified or within her limits, the following ones are tried, and the
+    /// rotation goes on after the one on-call. The days nobody of the rotation can take, and the other events, are left
+    /// to the solver.
+    /// The calendar maker is only updated when a solution is found.
+    pub fn solve_with_fixed_rotation(&mut self, rotation: &[Name], event: Event, max_subco: u8) {
+        let (calendar, availabilities) = (self.calendar.clone(), self.availabilities.clone());
+        // Same checks as the solver, which would otherwise keep whoever the rotation gives
+        let mut qualified_availabilities = self.qualified_availabilities();
+        let mut next = 0;
+        for day in self.calendar.get_empty_days(&event) {
+            let available = (0..rotation.len())
+                .map(|i| (next + i) % rotation.len())
+                .find(|i| {
+                    let name = &rotation[*i];
+                    qualified_availabilities
+                        .get(name)
+                        .and_then(|her_availabilities| her_availabilities.get(&day))
+                        .is_some_and(|events| events.contains(&event))
+                        && self.is_within_max_hours_per_week(&self.calendar, name, day, event)
+                        && self.is_within_contract(&self.calendar, name, day, event)
+                });
+            if let Some(i) = available {
+                self.calendar.set_for(day, event, rotation[i].clone());
+                let her_availabilities = qualified_availabilities.get_mut(&rotation[i]).unwrap();
                 Availabilities::update_availabilities(her_availabilities, day, event);
+                self.apply_optional_rules(her_availabilities, day, event);
+                next = i + 1;
             }
         }
+        self.availabilities = self.availabilities_for(&self.calendar);
+        self.make_calendar(max_subco, self.verbose);
+        if !self.empty_slots().is_empty() {
+            (self.calendar, self.availabilities) = (calendar, availabilities);
+        }
     }
 
-    /// Try all the permutations of the events, and return the first solution found.
-    fn try_all_permutations(&self) -> Result<(Calendar, AvailabilitiesPerPerson), ProblematicDays> {
-        let events = [
-            Event::FirstDaily,
-            Event::FirstNightly,
-            Event::SecondDaily,
-            Event::SecondNightly,
-        ];
-        let mut problematic_days = ProblematicDays::new();
-        let all_permutations_of_events = events.iter().permutations(events.len());
-        for permutation in all_permutations_of_events {
-            if self.verbose {
-                println!("Trying permutation {:?}", permutation);
+    /// Same as `make_calendar`, but a person on-call for any event of a group is also unavailable for all the events
+    /// of this group on the previous and the next day, even for the Second level on friday, saturday and sunday.
+    /// E.g. with `[SecondDaily, SecondNightly]`, nobody is on-call for the Second level two days in a row.
+    /// No subcontractor is added. Return the days / events left empty by the best partial calendar.
+    /// The calendar maker is only updated when a solution is found.
+    pub fn solve_with_event_groups(&mut self, groups: &[&[Event]]) -> Result<(), ScheduleError> {
+        self.event_groups = groups.iter().map(|group| group.to_vec()).collect();
+        let max_subcontractor = std::mem::replace(&mut self.max_subcontractor, 0);
+        let result = self.solve();
+        self.max_subcontractor = max_subcontractor;
+        self.event_groups.clear();
+        result.map_err(|best_partial| ScheduleError::Unfilled(Self::empty_slots_of(&best_partial)))
+    }
+
+    /// Same as `make_calendar`, but after each failed search, the events fully allocated by the best permutation are
+    /// kept, and the subcontractor is added to them. The next search only fills the remaining events, instead of
+    /// starting again from scratch. It's faster when many subcontractors are needed, but may need more of them, as
+    /// the allocations kept are never questioned. The calendar maker is only updated when a solution is found.
+    pub fn solve_with_feedback_loop(&mut self, max_subco: u8) -> Result<(), ScheduleError> {
+        self.calendar_made = true;
+        let saved = (
+            self.calendar.clone(),
+            self.availabilities.clone(),
+            self.initial_availabilities.clone(),
+            self.persons.clone(),
+            std::mem::take(&mut self.subco_explanations),
+        );
+        let mut i = 0;
+        let partial = loop {
+            let (problematic_days, partial) = match self.try_all_permutations() {
+                Ok((calendar, availabilities)) => {
+                    self.calendar = calendar;
+                    self.availabilities = availabilities;
+                    return Ok(());
+                }
+                Err(failure) => failure,
+            };
+            self.problematic_days = problematic_days.clone();
+            match problematic_days.iter().max_by_key(|e| e.1) {
+                Some((&(day, event), _)) if i < max_subco => {
+                    let subco_name = self.call_subco(i, day, event, &partial);
+                    // The subcontractor is on-call right away, the events already allocated are kept
+                    self.calendar = partial;
+                    self.calendar.set_for(day, event, subco_name);
+                    self.availabilities = self.availabilities_for(&self.calendar);
+                    i += 1;
+                }
+                _ => break partial,
             }
-            let mut solution_found_for_event = Vec::new();
-            // Start with a clear calendar and original availabilities
-            let mut calendar = self.calendar.clone();
-            let mut availabilities = self.availabilities.clone();
-            let mut problematic_day;
-            for &event in &permutation {
-                (calendar, availabilities, problematic_day) = self.make_calendar_for_event(
-                    &calendar.clone(),
-                    &availabilities.clone(),
-                    *event,
+        };
+        (
+            self.calendar,
+            self.availabilities,
+            self.initial_availabilities,
+            self.persons,
+            self.subco_explanations,
+        ) = saved;
+        Err(ScheduleError::Unfilled(Self::empty_slots_of(&partial)))
+    }
+
+    /// Alternative to `make_calendar`, minimizing the number of days / events given to subcontractors rather than
+    /// the number of subcontractors: each subcontractor is on-call only once. The days / events nobody is available
+    /// for get one first, as no solution exists without them, then the most problematic day / event is given one
+    /// after each failed search, up to `max_subco_days`.
+    /// Return the number of days / events given to subcontractors. The calendar maker is only updated on success.
+    pub fn solve_with_budget_constraint(
+        &mut self,
+        max_subco_days: u8,
+    ) -> Result<u8, ScheduleError> {
+        self.solve_with_subco_slots(Vec::new(), max_subco_days)
+    }
+
+    /// Same as `solve_with_budget_constraint`, but also giving the days / events passed in argument to
+    /// subcontractors, whoever is available for them.
+    fn solve_with_subco_slots(
+        &mut self,
+        forced_slots: Vec<(Date, Event)>,
+        max_subco_days: u8,
+    ) -> Result<u8, ScheduleError> {
+        self.calendar_made = true;
+        let mut maker = self.clone();
+        let mut subco_days = 0;
+        let mut pending_slots = self.report_infeasible_cells();
+        pending_slots.extend(forced_slots);
+        loop {
+            for (day, event) in pending_slots.drain(..) {
+                if subco_days == max_subco_days {
+                    return Err(ScheduleError::Unfilled(maker.report_infeasible_cells()));
+                }
+                maker.add_subco(
+                    &format!("{}{}", SUBCONTRACTOR_PREFIX, subco_days),
+                    day,
+                    event,
                 );
-                if calendar.get_empty_days(event).is_empty() {
-                    solution_found_for_event.push(event);
-                } else {
-                    if self.verbose {
-                        println!(" -> No solution found for event {:?}", event);
-                    }
-                    if let Some(problematic_day) = problematic_day {
-                        problematic_days
-                            .entry((problematic_day, *event))
-                            .and_modify(|v| *v += 1)
-                            .or_insert(0);
+                subco_days += 1;
+            }
+            match maker.try_all_permutations() {
+                Ok((calendar, availabilities)) => {
+                    maker.calendar = calendar;
+                    maker.availabilities = availabilities;
+                    *self = maker;
+                    return Ok(subco_days);
+                }
+                Err((problematic_days, best_partial)) => {
+                    match problematic_days.iter().max_by_key(|e| e.1) {
+                        Some((&most_problematic_day_and_event, _))
+                            if subco_days < max_subco_days =>
+                        {
+                            pending_slots.push(most_problematic_day_and_event)
+                        }
+                        _ => {
+                            return Err(ScheduleError::Unfilled(Self::empty_slots_of(
+                                &best_partial,
+                            )))
+                        }
                     }
-                    break;
                 }
             }
-            if solution_found_for_event.len() == events.len() {
-                return Ok((calendar, availabilities));
-            }
         }
-        Err(problematic_days)
     }
 
-    fn make_calendar_for_event(
-        &self,
-        calendar: &Calendar,
-        availabilities: &AvailabilitiesPerPerson,
-        event: Event,
-    ) -> (Calendar, AvailabilitiesPerPerson, Option<Date>) {
-        let (new_availabilities, new_calendar, problematic_day, _) =
-            Self::find_next(availabilities.clone(), calendar.clone(), event, 0);
-        if new_calendar.get_empty_days(&event).is_empty() {
-            return (new_calendar, new_availabilities, None);
+    /// Return calendars trading off the number of days / events given to subcontractors against fairness, none of
+    /// them being both fairer and with fewer subcontractor days than another one. Starting from the calendar with the
+    /// fewest subcontractor days, see `solve_with_budget_constraint`, one on-call of the busiest person is given to a
+    /// subcontractor, and the calendar made again, up to `max_subco` subcontractor days.
+    /// Sorted by number of subcontractor days. The calendar maker itself is left untouched.
+    pub fn solve_pareto(&self, max_subco: u8) -> Vec<ParetoPoint> {
+        let mut points: Vec<ParetoPoint> = Vec::new();
+        let mut forced_slots = Vec::new();
+        loop {
+            let mut maker = self.clone();
+            let Ok(subco_days) = maker.solve_with_subco_slots(forced_slots.clone(), max_subco)
+            else {
+                break;
+            };
+            let counts = maker.calendar.on_call_count_per_person();
+            let busiest = self
+                .persons()
+                .into_iter()
+                .max_by_key(|name| counts.get(&name.to_string()).copied().unwrap_or(0));
+            let busiest_slot = maker.calendar.get_all().iter().find_map(|(day, events)| {
+                events
+                    .iter()
+                    .find(|(event, name)| {
+                        Some(name.as_str()) == busiest && !forced_slots.contains(&(*day, **event))
+                    })
+                    .map(|(event, _)| (*day, *event))
+            });
+            points.push(ParetoPoint {
+                subco_days,
+                fairness_score: self.fairness_score(&maker.calendar),
+                calendar: maker.calendar,
+            });
+            match busiest_slot {
+                Some(slot) if subco_days < max_subco => forced_slots.push(slot),
+                _ => break,
+            }
         }
-        (calendar.clone(), availabilities.clone(), problematic_day)
+        let dominated = |point: &ParetoPoint| {
+            points.iter().any(|other| {
+                other.subco_days <= point.subco_days
+                    && other.fairness_score <= point.fairness_score
+                    && (other.subco_days < point.subco_days
+                        || other.fairness_score < point.fairness_score)
+            })
+        };
+        let mut frontier = points
+            .iter()
+            .filter(|point| !dominated(point))
+            .cloned()
+            .collect::<Vec<ParetoPoint>>();
+        frontier.sort_by_key(|point| point.subco_days);
+        frontier.dedup_by(|a, b| a.subco_days == b.subco_days);
+        frontier
     }
 
-    pub fn calendar_as_string(&self) -> String {
-        self.calendar.to_string()
+    /// Standard deviation of the number of on-calls in the calendar of each person of this calendar maker: the lower,
+    /// the fairer.
+    fn fairness_score(&self, calendar: &Calendar) -> f64 {
+        let (_, _, stddev) = self.on_call_count_distribution(calendar);
+        stddev
     }
 
-    /// Add a subcontractor for the day and event passed in argument.
-    fn add_subco_for_this_day_and_event(
-        &self,
-        availabilities: &HashMap<String, Availabilities>,
-        subco_name: &str,
-        day_ordinal: u16,
-        event: Event,
-    ) -> AvailabilitiesPerPerson {
-        let event_str = match event {
-            Event::FirstDaily => "1ère SF jour",
-            Event::FirstNightly => "1ère SF nuit",
-            Event::SecondDaily => "2ème SF jour",
-            Event::SecondNightly => "2ème SF nuit",
+    /// Same as `make_calendar`, but return the outcome of the solver instead of only updating the calendar maker.
+    pub fn make_calendar_report(&mut self, max_subco: u8) -> ScheduleReport {
+        let start = std::time::Instant::now();
+        let persons_before = self.availabilities.keys().cloned().collect::<Vec<Name>>();
+        self.make_calendar(max_subco, self.verbose);
+        let stats = SearchStats {
+            duration: start.elapsed(),
+            unfilled: self.empty_slots().len(),
         };
-        let mut availabilities_str = event_str.to_string();
-        for _ in self.calendar.from().ordinal()..=day_ordinal - 1 {
-            availabilities_str.push_str(",x");
-        }
-        availabilities_str.push(',');
-        for _ in day_ordinal + 1..=self.calendar.to().ordinal() {
-            availabilities_str.push_str(",x");
+        let subcontractors = self
+            .persons()
+            .into_iter()
+            .filter(|name| !persons_before.iter().any(|before| before == name))
+            .map(|name| name.to_string())
+            .collect();
+        let fairness_score = self.fairness_score(&self.calendar);
+        ScheduleReport {
+            calendar: self.calendar.clone(),
+            availabilities: self.availabilities.clone(),
+            problematic_days: self.problematic_days.clone(),
+            stats,
+            subcontractors,
+            fairness_score,
         }
-        let mut new_availabilities = availabilities.clone();
-        new_availabilities
-            .entry(subco_name.to_owned())
-            .and_modify(|a| a.merge(self.calendar.from(), &availabilities_str.to_string()))
-            .or_insert(Availabilities::from_str(
-                self.calendar.from(),
-                &availabilities_str.to_string(),
-            ));
-        new_availabilities
     }
 
-    /// Recursive function to find the next person for the next empty day
-    fn find_next(
-        availabilities: AvailabilitiesPerPerson,
-        calendar: Calendar,
-        event: Event,
-        recursion_depth: u16,
-    ) -> (AvailabilitiesPerPerson, Calendar, Option<Date>, u16) {
-        let availabilities = availabilities.clone();
-        let calendar = calendar.clone();
-        let mut problematic_day = None;
-        let remaining_days = calendar.get_empty_days(&event);
-        if !remaining_days.is_empty() {
-            let days_and_names =
-                Self::get_days_with_least_availabilities(&availabilities, &remaining_days, event);
-            // Check for premature stop, if there's 2 consecutive days with only the same person available
-            if Self::check_for_premature_stop(&days_and_names, &event) {
-                return (
-                    availabilities,
-                    calendar,
-                    problematic_day,
-                    recursion_depth + 1,
-                );
-            }
-            let mut all_permutations_of_days =
-                days_and_names.iter().permutations(days_and_names.len());
-            for (day, names) in all_permutations_of_days.next().unwrap() {
-                problematic_day = Some(*day);
-                if names.is_empty() {
-                    // No more possibilities, return the current state
-                    return (
-                        availabilities,
-                        calendar,
-                        problematic_day,
-                        recursion_depth + 1,
-                    );
-                }
-                // println!(
-                //     "Recursion depth: {}, Event: {:?}, Day: {}, Names: {:?}",
-                //     recursion_depth, event, day, names
-                // );
-                let sorted_by_least_on_call = Self::sort_names_by_least_on_call(names, &calendar);
-                let mut all_permutations_of_names = sorted_by_least_on_call
+    /// Run `make_calendar` `n` times, each time breaking the ties with another random seed, and return the distinct
+    /// calendars found with their fairness score, see `ScheduleReport`. The fairest calendars come first.
+    /// The seeds are drawn from the one set with `set_seed`, if any. The calendar maker itself is left untouched.
+    pub fn solve_n_times(&mut self, n: usize, max_subco: u8) -> Vec<(Calendar, f64)> {
+        let mut solutions: Vec<(Calendar, f64)> = Vec::new();
+        for _ in 0..n {
+            let mut maker = self.clone();
+            let seed = self.rng.get_mut().gen();
+            maker.set_tie_breaking_strategy(TieBreakingStrategy::Random(seed));
+            maker.set_seed(seed);
+            maker.make_calendar(max_subco, self.verbose);
+            if !maker.empty_slots().is_empty()
+                || solutions
                     .iter()
-                    .permutations(sorted_by_least_on_call.len());
-                for name in all_permutations_of_names.next().unwrap() {
-                    let mut new_calendar = calendar.clone();
-                    let mut new_availabilities = availabilities.clone();
-                    let new_recursion_depth;
-                    // Set the person for this day, and update her availabilities
-                    new_calendar.set_for(*day, event, name.clone());
-                    let her_availabilities = new_availabilities.get_mut(name).unwrap();
-                    Availabilities::update_availabilities(her_availabilities, *day, event);
-                    // Continue to find the next person for the next day
-                    (
-                        new_availabilities,
-                        new_calendar,
-                        problematic_day,
-                        new_recursion_depth,
-                    ) = Self::find_next(
-                        new_availabilities,
-                        new_calendar,
-                        event,
-                        recursion_depth + 1,
-                    );
-                    // Successful end condition is reached, return the result
-                    if new_calendar.get_empty_days(&event).is_empty() {
-                        return (new_availabilities, new_calendar, None, new_recursion_depth);
-                    }
-                }
+                    .any(|(calendar, _)| *calendar == maker.calendar)
+            {
+                continue;
             }
+            let fairness_score = maker.fairness_score(&maker.calendar);
+            solutions.push((maker.calendar, fairness_score));
         }
-        (availabilities, calendar, problematic_day, recursion_depth)
+        solutions.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        solutions
     }
 
-    /// Sort the names by the least on-call days, allow to balance the on-call days between all the persons
-    fn sort_names_by_least_on_call(names: &[Name], calendar: &Calendar) -> Vec<Name> {
-        let mut names_and_count = HashMap::new();
-        for name in names.iter() {
-            let count = calendar
-                .get_all()
-                .values()
-                .filter(|f| Self::is_on_call(f, name))
-                .count();
-            names_and_count.insert(name, count);
+    /// Make the calendar for the whole month, whatever the range of the CSV file.
+    /// The days already allocated within this month are kept; persons are unavailable on the days missing from the CSV file.
+    /// The availabilities outside of the month are kept, so that another month can be solved afterwards.
+    pub fn solve_for_month(
+        &mut self,
+        year: i32,
+        month: time::Month,
+        max_subco: u8,
+    ) -> Result<(), AubepineError> {
+        let from = Date::from_calendar_date(year, month, 1)
+            .map_err(|_| AubepineError::InvalidYear(year))?;
+        let to = Date::from_calendar_date(year, month, month.length(year)).unwrap();
+        self.calendar = self.calendar_within(from, to);
+        self.availabilities = self.availabilities_for(&self.calendar);
+        for availabilities in self.availabilities.values_mut() {
+            availabilities.trim(from, to);
         }
-        let sorted_names = names
-            .iter()
-            .sorted_by_key(|n| names_and_count.get(n).unwrap())
-            .cloned()
-            .collect();
-        sorted_names
+        self.make_calendar(max_subco, self.verbose);
+        Ok(())
     }
 
-    /// Return true if the person designated by `name` is on call in one of the event passed in argument `availabilities`
-    fn is_on_call(availabilities: &HashMap<Event, Name>, name: &Name) -> bool {
-        for event in [
-            Event::FirstDaily,
-            Event::FirstNightly,
-            Event::SecondDaily,
-            Event::SecondNightly,
-        ] {
-            if let Some(on_call) = availabilities.get(&event) {
-                if name == on_call {
-                    return true;
+    /// Return the first and the last day of the calendar.
+    pub fn calendar_range(&self) -> (Date, Date) {
+        (self.calendar.from(), self.calendar.to())
+    }
+
+    /// Change the range of the calendar, e.g. when the header of the CSV file is wrong.
+    /// The days already allocated within the new range are kept, and the availabilities outside of it are removed.
+    pub fn set_calendar_range(&mut self, from: Date, to: Date) -> Result<(), AubepineError> {
+        if from > to {
+            return Err(AubepineError::InvalidRange(from, to));
+        }
+        let calendar = self.calendar_within(from, to);
+        for availabilities in self.initial_availabilities.values_mut() {
+            availabilities.trim(from, to);
+        }
+        self.availabilities = self.availabilities_for(&calendar);
+        self.calendar = calendar;
+        Ok(())
+    }
+
+    /// Return an empty calendar from `from` to `to`, with the days already allocated within this range.
+    fn calendar_within(&self, from: Date, to: Date) -> Calendar {
+        let mut calendar = Calendar::new(from, to);
+        for (day, events) in self.calendar.get_all() {
+            if day.is_in_range(from, to) {
+                for (event, name) in events {
+                    calendar.set_for(*day, *event, name.clone());
                 }
             }
         }
-        false
+        calendar
     }
 
-    /// Return true if there's 2 consecutive week days with only the same person available
-    fn check_for_premature_stop(days_and_names: &[(Date, Vec<Name>)], event: &Event) -> bool {
-        if days_and_names.len() < 2 {
-            return false;
+    /// Same as `make_calendar`, but the recursive solver runs on tokio's blocking thread pool, so that it doesn't
+    /// block the async executor. Return an error if some days / events are still empty once solved.
+    #[cfg(feature = "tokio")]
+    pub async fn make_calendar_async(&mut self, max_subco: u8) -> Result<(), ScheduleError> {
+        use std::sync::{Arc, Mutex};
+
+        let verbose = self.verbose;
+        let calendar_maker = Arc::new(Mutex::new(self.clone()));
+        let solver = Arc::clone(&calendar_maker);
+        tokio::task::spawn_blocking(move || {
+            solver.lock().unwrap().make_calendar(max_subco, verbose);
+        })
+        .await
+        .map_err(|_| ScheduleError::Aborted)?;
+        *self = calendar_maker.lock().unwrap().clone();
+        let empty_slots = self.empty_slots();
+        if !empty_slots.is_empty() {
+            return Err(ScheduleError::Unfilled(empty_slots));
         }
-        for i in 0..days_and_names.len() - 1 {
-            // continue if there's more than one person available
-            if days_and_names[i].1.len() != 1 {
-                continue;
+        Ok(())
+    }
+
+    /// Same as `make_calendar`, but showing the permutations of the events tried and the number of subcontractors on
+    /// a progress bar. The progress bar is drawn on stderr, so that the calendar can still be piped from stdout.
+    #[cfg(feature = "progress")]
+    pub fn make_calendar_with_progress_bar(&mut self, max_subcontractor: u8, verbose: bool) {
+        let progress_bar = indicatif::ProgressBar::new(0);
+        progress_bar.set_style(
+            indicatif::ProgressStyle::with_template("{bar:40} {pos}/{len} permutations, {msg}")
+                .unwrap(),
+        );
+        let previous_callback = self.on_progress.take();
+        let bar = progress_bar.clone();
+        self.set_on_progress(move |progress| {
+            bar.set_length(progress.permutation_count as u64);
+            bar.set_position(progress.permutations_tried as u64);
+            bar.set_message(format!("{} subcontractor(s)", progress.subcontractors));
+        });
+        self.make_calendar(max_subcontractor, verbose);
+        progress_bar.finish();
+        self.on_progress = previous_callback;
+    }
+
+    /// Fill only the days of the calendar within the ISO week passed in argument, leaving the other weeks untouched.
+    /// When the calendar spans more than a year, the week is the first one with this number, e.g. the week 1 of the
+    /// first year. The on-calls already allocated on the neighbouring days are taken into account, and the maximum
+    /// number of subcontractors is the one of the last call to `make_calendar`.
+    /// Return the calendar of the week, or the days / events that are still empty.
+    pub fn make_calendar_for_week(&mut self, week: u8) -> Result<Calendar, ScheduleError> {
+        let iso_year_and_week = |day: &Date| {
+            let (iso_year, week, _) = day.to_iso_week_date();
+            (iso_year, week)
+        };
+        let Some(first_day) = self.calendar.days().find(|day| day.iso_week() == week) else {
+            return Err(ScheduleError::OutOfRange);
+        };
+        let days = self
+            .calendar
+            .days()
+            .filter(|day| iso_year_and_week(day) == iso_year_and_week(&first_day))
+            .collect::<Vec<Date>>();
+        let (Some(&from), Some(&to)) = (days.first(), days.last()) else {
+            return Err(ScheduleError::OutOfRange);
+        };
+
+        self.calendar_made = true;
+        let mut week_maker = self.clone();
+        week_maker.set_calendar_range(from, to).unwrap();
+        // Unlike `set_calendar_range`, keep the constraints of the on-calls of the other weeks
+        week_maker.availabilities = self.availabilities_for(&self.calendar);
+        for her_availabilities in week_maker.availabilities.values_mut() {
+            her_availabilities.trim(from, to);
+        }
+        week_maker.make_calendar(self.max_subcontractor, self.verbose);
+        let empty_slots = week_maker.empty_slots();
+        if !empty_slots.is_empty() {
+            return Err(ScheduleError::Unfilled(empty_slots));
+        }
+
+        // Subcontractors added for this week, possibly with the name of one already on-call on other days
+        for (name, her_availabilities) in week_maker.initial_availabilities {
+            match self.initial_availabilities.get_mut(&name) {
+                Some(availabilities) => availabilities.extend(her_availabilities),
+                None => {
+                    self.initial_availabilities.insert(name, her_availabilities);
+                }
             }
-            // Continue if one of the day is a week-end, and we're searching a person available for a Second level event
-            let is_second_level = event == &Event::SecondDaily || event == &Event::SecondNightly;
-            let one_of_the_day_is_weekend =
-                Self::is_weekend(days_and_names[i].0) || Self::is_weekend(days_and_names[i + 1].0);
-            if one_of_the_day_is_weekend && is_second_level {
-                continue;
+        }
+        for (name, person) in week_maker.persons {
+            self.persons.entry(name).or_insert(person);
+        }
+        for (day, events) in week_maker.calendar.get_all() {
+            for (event, name) in events {
+                self.calendar.set_for(*day, *event, name.clone());
             }
-            // Return true if there's 2 consecutive days with only the same person available
-            let are_consecutive_days = days_and_names[i]
+        }
+        self.availabilities = self.availabilities_for(&self.calendar);
+        Ok(week_maker.calendar)
+    }
+
+    /// Return a calendar maker with the same persons, availabilities and rules, but without any on-call allocated,
+    /// including the ones of the input file. Cheaper than cloning it, as the calendar and the availabilities derived
+    /// from it are not copied.
+    pub fn clone_without_assignments(&self) -> Self {
+        Self {
+            calendar: Calendar::new(self.calendar.from(), self.calendar.to()),
+            availabilities: self.initial_availabilities.clone(),
+            initial_availabilities: self.initial_availabilities.clone(),
+            persons: self.persons.clone(),
+            min_seniority_per_event: self.min_seniority_per_event.clone(),
+            qualified_events: self.qualified_events.clone(),
+            on_call_chains: self.on_call_chains.clone(),
+            warnings: self.warnings.clone(),
+            problematic_days: ProblematicDays::new(),
+            subco_explanations: Vec::new(),
+            max_subcontractor: self.max_subcontractor,
+            verbose: self.verbose,
+            calendar_made: false,
+            balancing_strategy: self.balancing_strategy,
+            imported_balance: self.imported_balance.clone(),
+            tie_breaking_strategy: self.tie_breaking_strategy,
+            first_assignment_strategy: self.first_assignment_strategy,
+            scarce_persons_first: self.scarce_persons_first,
+            employees_first: self.employees_first,
+            event_groups: self.event_groups.clone(),
+            max_gap: self.max_gap,
+            rest_hours_after_night: self.rest_hours_after_night,
+            max_weekly_oncall_hours: self.max_weekly_oncall_hours,
+            holidays: self.holidays.clone(),
+            avoid_same_day_double: self.avoid_same_day_double,
+            senior_night_pairing: self.senior_night_pairing,
+            seed: self.seed,
+            rng: self.rng.clone(),
+            on_progress: self.on_progress.clone(),
+        }
+    }
+
+    /// Return how many days are fully covered or not, and how many days each event is allocated.
+    pub fn coverage_report(&self) -> CoverageReport {
+        if !self.calendar_made {
+            return CoverageReport::default();
+        }
+        let mut unfilled_per_day = self
+            .calendar
+            .days()
+            .map(|day| (day, 0))
+            .collect::<BTreeMap<Date, usize>>();
+        let mut per_event = BTreeMap::new();
+        for event in Event::ALL {
+            let empty_days = self.calendar.get_empty_days(&event);
+            for day in &empty_days {
+                *unfilled_per_day.entry(*day).or_default() += 1;
+            }
+            let planned = self.calendar.day_count();
+            per_event.insert(
+                event,
+                EventCoverage {
+                    planned,
+                    filled: planned - empty_days.len(),
+                },
+            );
+        }
+        let day_count = |filter: fn(usize) -> bool| {
+            Some(unfilled_per_day.values().filter(|&&n| filter(n)).count())
+        };
+        CoverageReport {
+            fully_covered_days: day_count(|n| n == 0),
+            days_with_one_unfilled: day_count(|n| n == 1),
+            days_with_several_unfilled: day_count(|n| n >= 2),
+            per_event: Some(per_event),
+        }
+    }
+
+    /// Return the days and events for which nobody is on-call yet.
+    fn empty_slots(&self) -> Vec<(Date, Event)> {
+        Self::empty_slots_of(&self.calendar)
+    }
+
+    /// Return the days and events for which nobody is on-call in the calendar passed in argument.
+    fn empty_slots_of(calendar: &Calendar) -> Vec<(Date, Event)> {
+        let mut slots = Vec::new();
+        for event in Event::ALL {
+            for day in calendar.get_empty_days(&event) {
+                slots.push((day, event));
+            }
+        }
+        slots.sort();
+        slots
+    }
+
+    /// Return the names of all the persons who can be on-call, including the subcontractors added so far, sorted.
+    pub fn persons(&self) -> Vec<&str> {
+        self.availabilities
+            .keys()
+            .map(|name| name.as_str())
+            .sorted()
+            .collect()
+    }
+
+    pub fn person_count(&self) -> usize {
+        self.availabilities.len()
+    }
+
+    /// Add an availability row, i.e. the on-call level followed by the availabilities of each day from `from`, to the
+    /// person designated by `name`. The person is created if she doesn't exist yet.
+    pub fn merge_availabilities_for_person(
+        &mut self,
+        name: &str,
+        from: Date,
+        line: &str,
+    ) -> Result<(), AubepineError> {
+        availabilities::try_extract_availability_info(line)?;
+        let to = self.calendar.to();
+        let warning = match self.initial_availabilities.get_mut(name) {
+            Some(her_availabilities) => her_availabilities.merge(from, to, line),
+            None => {
+                let (her_availabilities, warning) = Availabilities::from_str(from, to, line);
+                self.initial_availabilities
+                    .insert(name.to_string(), her_availabilities);
+                self.persons
+                    .entry(name.to_string())
+                    .or_insert_with(|| Person::from_csv_name(name, SUBCONTRACTOR_PREFIX));
+                warning
+            }
+        };
+        if let Some(warning) = warning {
+            self.warnings.push(format!("{}: {}", name, warning));
+        }
+        self.availabilities = self.availabilities_for(&self.calendar);
+        Ok(())
+    }
+
+    /// Add a subcontractor, named as wanted rather than after `SUBCONTRACTOR_PREFIX`, available only for the day and
+    /// event passed in argument. The solver allocates her like anyone else, the consecutive days rule included.
+    /// When she already exists, this day and event are added to her availabilities.
+    pub fn force_subcontractor(
+        &mut self,
+        name: &str,
+        day: Date,
+        event: Event,
+    ) -> Result<(), AubepineError> {
+        if !day.is_in_range(self.calendar.from(), self.calendar.to()) {
+            return Err(AubepineError::DateOutOfRange(day));
+        }
+        self.add_subco(name, day, event);
+        Ok(())
+    }
+
+    /// Remove the subcontractors added by the solver, i.e. the persons named after `SUBCONTRACTOR_PREFIX`, and their
+    /// allocations, e.g. to call `make_calendar` again once the availabilities of the employees are updated.
+    /// The allocations of the other persons are kept.
+    pub fn remove_subcontractors(&mut self) {
+        self.retain_persons(|name| !name.starts_with(SUBCONTRACTOR_PREFIX));
+        self.subco_explanations.clear();
+    }
+
+    /// Make the calendar only with the persons of `pool`, e.g. to check whether part of the team can cover a shorter
+    /// period, with the maximum number of subcontractors of the last call to `make_calendar`. The allocations of the
+    /// other persons are dropped, and the unknown names of `pool` ignored. The calendar maker is left untouched.
+    /// Return the days / events left empty on failure.
+    pub fn solve_for_person_pool(&self, pool: &[&str]) -> Result<Calendar, ScheduleError> {
+        let mut pool_maker = self.clone();
+        pool_maker.retain_persons(|name| pool.contains(&name.as_str()));
+        pool_maker.make_calendar(self.max_subcontractor, self.verbose);
+        match pool_maker.empty_slots() {
+            empty_slots if empty_slots.is_empty() => Ok(pool_maker.calendar),
+            empty_slots => Err(ScheduleError::Unfilled(empty_slots)),
+        }
+    }
+
+    /// Remove the persons for which `keep` returns false, and their allocations.
+    fn retain_persons(&mut self, keep: impl Fn(&Name) -> bool) {
+        let removed_allocations = self
+            .calendar
+            .get_all()
+            .iter()
+            .flat_map(|(day, events)| {
+                events
+                    .iter()
+                    .filter(|(_, name)| !keep(name))
+                    .map(move |(event, _)| (*day, *event))
+            })
+            .collect::<Vec<_>>();
+        for (day, event) in removed_allocations {
+            self.calendar.unset_for(&day, &event);
+        }
+        self.persons.retain(|name, _| keep(name));
+        self.initial_availabilities.retain(|name, _| keep(name));
+        self.availabilities = self.availabilities_for(&self.calendar);
+    }
+
+    /// Return the person designated by `name`, e.g. to set her seniority.
+    pub fn person_mut(&mut self, name: &str) -> Option<&mut Person> {
+        self.persons.get_mut(name)
+    }
+
+    /// Only allow the persons with at least the seniority `min` to be on-call for `event`.
+    /// Subcontractors are assumed to be qualified for any event.
+    pub fn require_min_seniority_for_event(&mut self, event: Event, min: Seniority) {
+        self.min_seniority_per_event.insert(event, min);
+    }
+
+    /// Only allow the person designated by `name` to be on-call for these events, even on the days she is available
+    /// for others, e.g. when the Second level requires a certification. By default, a person is qualified for all the
+    /// events.
+    pub fn set_qualified_events(&mut self, name: &str, events: &[Event]) {
+        self.qualified_events
+            .insert(name.to_string(), events.to_vec());
+    }
+
+    /// Same as `make_calendar`, after restricting each person of `roles` to the events she is qualified for, see
+    /// `set_qualified_events`. The restrictions are kept for the next calls.
+    pub fn solve_with_role_constraints(&mut self, roles: &RoleMatrix, max_subco: u8) {
+        for (name, events) in roles {
+            self.set_qualified_events(name, events);
+        }
+        self.make_calendar(max_subco, self.verbose);
+    }
+
+    /// Mark the person designated by `name` as unavailable on all the days covered by the events of an iCalendar,
+    /// e.g. exported from her personal calendar.
+    pub fn block_from_ical(&mut self, name: &str, ical_str: &str) -> Result<(), AubepineError> {
+        let unavailabilities = Availabilities::from_ical(ical_str)?;
+        if !self.availabilities.contains_key(name) {
+            return Err(AubepineError::UnknownPerson(name.to_string()));
+        }
+        for availabilities in [&mut self.availabilities, &mut self.initial_availabilities] {
+            let her_availabilities = availabilities.get_mut(name).unwrap();
+            for day in unavailabilities.get_all().keys() {
+                her_availabilities.pop_all(day);
+            }
+        }
+        Ok(())
+    }
+
+    /// Assess the impact of the person designated by `name` being absent on these days: starting from the current
+    /// calendar, her on-calls on these days are given to others by `make_calendar`, with the maximum number of
+    /// subcontractors of its last call, and the result is compared to the current calendar. The other on-calls are
+    /// kept. The calendar maker itself is left untouched.
+    pub fn simulate_absence(
+        &self,
+        name: &str,
+        days: &[Date],
+    ) -> Result<SimulationResult, AubepineError> {
+        if !self.availabilities.contains_key(name) {
+            return Err(AubepineError::UnknownPerson(name.to_string()));
+        }
+        let mut absent = self.clone();
+        absent.problematic_days.clear();
+        for (day, event) in self.calendar.assignments_for_person(name) {
+            if days.contains(&day) {
+                absent.calendar.unset_for(&day, &event);
+            }
+        }
+        let her_availabilities = absent.initial_availabilities.get_mut(name).unwrap();
+        for day in days {
+            her_availabilities.pop_all(day);
+        }
+        absent.availabilities = absent.availabilities_for(&absent.calendar);
+        let person_count = absent.person_count();
+        absent.make_calendar(self.max_subcontractor, self.verbose);
+        Ok(SimulationResult {
+            feasible: absent.empty_slots().is_empty(),
+            subcontractors_needed: (absent.person_count() - person_count) as u8,
+            problematic: absent.problematic_days.keys().copied().collect(),
+            fairness_delta: self.fairness_score(&absent.calendar)
+                - self.fairness_score(&self.calendar),
+        })
+    }
+
+    /// Estimate the smallest team able to fill the calendar with at most `target_subco` subcontractors: the persons
+    /// of the calendar maker, plus as few new persons available for everything as needed, found by binary search.
+    /// The calendars are made from scratch, without the allocations of the input file.
+    pub fn compute_minimum_team_size(&self, target_subco: u8) -> u8 {
+        let succeeds_with = |new_persons: usize| {
+            let mut maker = self.clone_without_assignments();
+            maker.add_full_time_persons(new_persons);
+            maker.make_calendar(target_subco, false);
+            maker.empty_slots().is_empty()
+        };
+        // Each new person can take one of the days / events on her own
+        let (mut low, mut high) = (0, self.calendar.day_count() * 4);
+        while low < high {
+            let middle = (low + high) / 2;
+            if succeeds_with(middle) {
+                high = middle;
+            } else {
+                low = middle + 1;
+            }
+        }
+        (self.person_count() + low).min(u8::MAX as usize) as u8
+    }
+
+    /// Add employees available for all the days and events, named `NEW-0`, `NEW-1`, etc.
+    fn add_full_time_persons(&mut self, count: usize) {
+        for i in 0..count {
+            let name = format!("NEW-{}", i);
+            let mut her_availabilities = Availabilities::new();
+            for day in self.calendar.days() {
+                her_availabilities.set_available(day, &Event::ALL);
+            }
+            for availabilities in [&mut self.availabilities, &mut self.initial_availabilities] {
+                availabilities.insert(name.clone(), her_availabilities.clone());
+            }
+            self.persons
+                .entry(name.clone())
+                .or_insert_with(|| Person::new_employee(&name));
+        }
+    }
+
+    /// Put `new_person` on-call for this day and event instead of the person currently on-call, e.g. after a manual swap.
+    /// Unless `force` is true, `new_person` must be available, taking into account her other on-call days.
+    /// The availabilities of both persons are updated accordingly.
+    pub fn reassign(
+        &mut self,
+        day: Date,
+        event: Event,
+        new_person: &str,
+        force: bool,
+    ) -> Result<(), AubepineError> {
+        if !self.initial_availabilities.contains_key(new_person) {
+            return Err(AubepineError::UnknownPerson(new_person.to_string()));
+        }
+        if !day.is_in_range(self.calendar.from(), self.calendar.to()) {
+            return Err(AubepineError::DateOutOfRange(day));
+        }
+        let mut calendar = self.calendar.clone();
+        calendar.unset_for(&day, &event);
+        let availabilities = self.availabilities_for(&calendar);
+        let is_available = availabilities
+            .get(new_person)
+            .and_then(|a| a.get(&day))
+            .is_some_and(|events| events.contains(&event));
+        if !is_available && !force {
+            return Err(AubepineError::NotAvailable(
+                new_person.to_string(),
+                day,
+                event,
+            ));
+        }
+        calendar.set_for(day, event, new_person.to_string());
+        self.availabilities = self.availabilities_for(&calendar);
+        self.calendar = calendar;
+        Ok(())
+    }
+
+    /// Apply swaps approved beforehand, each one putting the second person on-call for the day and event instead of the
+    /// first one, then call `make_calendar` with up to `max_subco` subcontractors.
+    /// The swaps are applied in order, with the same checks as `reassign`. If one of them fails, none is applied and
+    /// the calendar isn't made.
+    pub fn solve_with_swap_table(
+        &mut self,
+        swaps: &[(Date, Event, Name, Name)],
+        max_subco: u8,
+    ) -> Result<(), AubepineError> {
+        let (calendar, availabilities) = (self.calendar.clone(), self.availabilities.clone());
+        for (day, event, from, to) in swaps {
+            let result = match self.calendar.get_for(day, event) {
+                Some(name) if name == from => self.reassign(*day, *event, to, false),
+                _ => Err(AubepineError::NotOnCall(from.clone(), *day, *event)),
+            };
+            if result.is_err() {
+                (self.calendar, self.availabilities) = (calendar, availabilities);
+                return result;
+            }
+        }
+        self.make_calendar(max_subco, self.verbose);
+        Ok(())
+    }
+
+    /// Apply the contract of each person: the events she's qualified for, her maximum number of hours per week and per
+    /// month, and her minimum rest between two events. Then call `make_calendar` with up to `max_subco`
+    /// subcontractors. The contracts of unknown persons are ignored, and all are kept for the next calls.
+    pub fn solve_respecting_contracts(
+        &mut self,
+        contracts: HashMap<Name, ContractType>,
+        max_subco: u8,
+    ) {
+        for (name, contract) in contracts {
+            let Some(person) = self.persons.get_mut(&name) else {
+                continue;
+            };
+            person
+                .set_max_hours_per_week(u8::try_from(contract.max_weekly_hours).unwrap_or(u8::MAX));
+            person.set_max_hours_per_month(contract.max_monthly_hours);
+            person.set_min_rest_hours(contract.min_rest_between_hours);
+            self.set_qualified_events(&name, &contract.allowed_events);
+        }
+        self.make_calendar(max_subco, self.verbose);
+    }
+
+    /// Return the initial availabilities, updated with all the on-call days and events of the calendar.
+    fn availabilities_for(&self, calendar: &Calendar) -> AvailabilitiesPerPerson {
+        let mut availabilities = self.initial_availabilities.clone();
+        for (day, events) in calendar.get_all() {
+            for (event, name) in events {
+                if let Some(her_availabilities) = availabilities.get_mut(name) {
+                    Availabilities::update_availabilities(her_availabilities, *day, *event);
+                }
+            }
+        }
+        availabilities
+    }
+
+    /// Check the input without running the solver, and return a list of warnings.
+    /// Warns about the problems found while parsing the input, about every day / event for which nobody is available, and about persons who are never available.
+    pub fn validate(&self) -> Vec<String> {
+        let mut warnings = self.warnings.clone();
+        for (day, event) in self.report_infeasible_cells() {
+            warnings.push(format!("Nobody is available on {} for {:?}", day, event));
+        }
+        for name in self.availabilities.keys().sorted() {
+            let availabilities = self.availabilities.get(name).unwrap();
+            let is_never_available = availabilities
+                .get_all()
+                .values()
+                .all(|events| events.is_empty());
+            if is_never_available {
+                warnings.push(format!("{} is never available", name));
+                continue;
+            }
+            for event in Event::ALL {
+                let density = availabilities.coverage_density(event);
+                if density > 0.0 && density < LOW_COVERAGE_DENSITY {
+                    warnings.push(format!(
+                        "{} is available only {:.0}% of the days for {:?}",
+                        name,
+                        density * 100.0,
+                        event
+                    ));
+                }
+            }
+        }
+        warnings
+    }
+
+    /// Return the persons available on the day for the event, sorted by name.
+    pub fn available_persons_for(&self, day: Date, event: Event) -> Vec<&Name> {
+        Self::persons_available(&self.availabilities, &day, event)
+    }
+
+    /// Return the days not allocated yet for the event, for which only one person is available.
+    /// These bottlenecks are the days most likely to require a subcontractor.
+    pub fn days_with_only_one_available_person(&self, event: Event) -> Vec<Date> {
+        self.calendar
+            .get_empty_days(&event)
+            .into_iter()
+            .filter(|day| self.available_persons_for(*day, event).len() == 1)
+            .collect()
+    }
+
+    /// Return the days not allocated yet for the event, for which nobody is available.
+    /// The calendar can't be made unless a subcontractor is added for each of them, see `report_infeasible_cells`.
+    pub fn days_without_any_available_person(&self, event: Event) -> Vec<Date> {
+        self.calendar
+            .get_empty_days(&event)
+            .into_iter()
+            .filter(|day| self.available_persons_for(*day, event).is_empty())
+            .collect()
+    }
+
+    /// Return the days and events that are not allocated yet and for which nobody is available.
+    /// Each of them requires a subcontractor, so the calendar can't be made with fewer subcontractors than this.
+    pub fn report_infeasible_cells(&self) -> Vec<(Date, Event)> {
+        let availabilities = self.qualified_availabilities();
+        let mut cells = Vec::new();
+        for event in Event::ALL {
+            let empty_days = self.calendar.get_empty_days(&event);
+            if empty_days.is_empty() {
+                continue;
+            }
+            for (day, names) in
+                Self::get_days_with_least_availabilities(&availabilities, &empty_days, event)
+            {
+                if names.is_empty() {
+                    cells.push((day, event));
+                }
+            }
+        }
+        cells.sort();
+        cells
+    }
+
+    /// Seed the random number generator used for all the random decisions, so that two runs with the same seed
+    /// and the same input produce the same calendar. The random strategies, e.g. `TieBreakingStrategy::Random`, then
+    /// draw from it, the strategies themselves being left unchanged.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+        self.rng = RefCell::new(SmallRng::seed_from_u64(seed));
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Call `callback` each time the solver is done with a permutation of the events, e.g. to show the progress of a
+    /// long search.
+    pub fn set_on_progress(&mut self, callback: impl Fn(SearchProgress) + Send + Sync + 'static) {
+        self.on_progress = Some(ProgressCallback(Arc::new(callback)));
+    }
+
+    /// Choose how the solver orders the persons available for a day, see `BalancingStrategy`.
+    pub fn set_balancing_strategy(&mut self, strategy: BalancingStrategy) {
+        self.balancing_strategy = strategy;
+    }
+
+    /// Set the number of on-call days of each person in the previous calendars, e.g. the ones of the last months,
+    /// counted by `BalancingStrategy::CarryOver`. The persons missing from `balance` have none.
+    pub fn import_balance(&mut self, balance: &HashMap<Name, usize>) {
+        self.imported_balance = balance.clone();
+    }
+
+    /// Choose how the solver orders the persons the balancing strategy ranks equally, see `TieBreakingStrategy`.
+    pub fn set_tie_breaking_strategy(&mut self, strategy: TieBreakingStrategy) {
+        self.tie_breaking_strategy = strategy;
+    }
+
+    /// Choose which remaining day the solver fills first, see `FirstAssignmentStrategy`.
+    pub fn set_first_assignment_strategy(&mut self, strategy: FirstAssignmentStrategy) {
+        self.first_assignment_strategy = strategy;
+    }
+
+    /// Same as `make_calendar`, but the solver fills the last remaining day of the month first, then the days with the
+    /// least available persons.
+    pub fn solve_with_deadline_first(&mut self, max_subco: u8) {
+        let previous = self.first_assignment_strategy;
+        self.first_assignment_strategy = FirstAssignmentStrategy::DeadlineFirst;
+        self.make_calendar(max_subco, self.verbose);
+        self.first_assignment_strategy = previous;
+    }
+
+    fn take_initial_allocations(&mut self, lines: std::str::Lines) {
+        // Skip the first line, it's the header
+        let lines = lines.skip(1);
+        for line in lines {
+            let (name, availabilities_str) = line.split_once([',', ';']).expect("Name missing");
+            let on_call_allocations =
+                Availabilities::parse_initial_allocations(self.calendar.from(), availabilities_str);
+            for (day, event) in on_call_allocations {
+                self.calendar.set_for(day, event, name.to_string());
+                let her_availabilities = self.availabilities.get_mut(name).unwrap();
+                Availabilities::update_availabilities(her_availabilities, day, event);
+            }
+        }
+    }
+
+    /// Try all the permutations of the events, and return the first solution found.
+    /// On failure, return the problematic days, and the calendar of the permutation which allocated the most events.
+    fn try_all_permutations(
+        &self,
+    ) -> Result<(Calendar, AvailabilitiesPerPerson), (ProblematicDays, Calendar)> {
+        let events = Event::ALL;
+        let mut problematic_days = ProblematicDays::new();
+        let mut best_partial = self.calendar.clone();
+        let empty_slot_count = |calendar: &Calendar| {
+            events
+                .iter()
+                .map(|event| calendar.get_empty_days(event).len())
+                .sum::<usize>()
+        };
+        // Start with the most constrained events, as it's the most likely order to find a solution quickly
+        let optimized_order = self.optimize_event_order();
+        let all_permutations_of_events = std::iter::once(optimized_order.iter().collect()).chain(
+            events
+                .iter()
+                .permutations(events.len())
+                .filter(|permutation| !permutation.iter().copied().eq(optimized_order.iter())),
+        );
+        let permutation_count = (1..=events.len()).product();
+        let subcontractors = self
+            .persons
+            .values()
+            .filter(|person| person.is_subcontractor())
+            .count();
+        for (i, permutation) in all_permutations_of_events.enumerate() {
+            if self.verbose {
+                println!("Trying permutation {:?}", permutation);
+            }
+            let mut solution_found_for_event = Vec::new();
+            // Start with a clear calendar and original availabilities
+            let mut calendar = self.calendar.clone();
+            let mut availabilities = self.qualified_availabilities();
+            let mut problematic_day;
+            for &event in &permutation {
+                (calendar, availabilities, problematic_day) = self.make_calendar_for_event(
+                    &calendar.clone(),
+                    &availabilities.clone(),
+                    *event,
+                );
+                if calendar.get_empty_days(event).is_empty() {
+                    solution_found_for_event.push(event);
+                } else {
+                    if self.verbose {
+                        println!(" -> No solution found for event {:?}", event);
+                    }
+                    if let Some(problematic_day) = problematic_day {
+                        problematic_days
+                            .entry((problematic_day, *event))
+                            .and_modify(|v| *v += 1)
+                            .or_insert(0);
+                    }
+                    break;
+                }
+            }
+            if let Some(ProgressCallback(callback)) = &self.on_progress {
+                callback(SearchProgress {
+                    permutations_tried: i + 1,
+                    permutation_count,
+                    subcontractors,
+                });
+            }
+            if solution_found_for_event.len() == events.len() {
+                return Ok((calendar, availabilities));
+            }
+            if empty_slot_count(&calendar) < empty_slot_count(&best_partial) {
+                best_partial = calendar;
+            }
+        }
+        Err((problematic_days, best_partial))
+    }
+
+    /// Alternative to `make_calendar`, for organisations where the two levels are staffed by different pools:
+    /// solve first the First level events, then the Second level ones. As the persons are sorted by the least
+    /// on-call, the allocations of the first pass are taken into account to balance the second one.
+    /// No subcontractor is added. Return the days / events of the first pass that can't be filled.
+    pub fn solve_multi_pass(&mut self) -> Result<(), ScheduleError> {
+        self.calendar_made = true;
+        let mut calendar = self.calendar.clone();
+        let mut availabilities = self.qualified_availabilities();
+        for pass in [
+            [Event::FirstDaily, Event::FirstNightly],
+            [Event::SecondDaily, Event::SecondNightly],
+        ] {
+            let solution = pass.iter().permutations(pass.len()).find_map(|order| {
+                let (mut new_calendar, mut new_availabilities) =
+                    (calendar.clone(), availabilities.clone());
+                for &event in order {
+                    (new_calendar, new_availabilities, _) =
+                        self.make_calendar_for_event(&new_calendar, &new_availabilities, event);
+                    if !new_calendar.get_empty_days(&event).is_empty() {
+                        return None;
+                    }
+                }
+                Some((new_calendar, new_availabilities))
+            });
+            match solution {
+                Some(solution) => (calendar, availabilities) = solution,
+                None => {
+                    let unfilled = pass
+                        .iter()
+                        .flat_map(|event| {
+                            calendar
+                                .get_empty_days(event)
+                                .into_iter()
+                                .map(|day| (day, *event))
+                        })
+                        .sorted()
+                        .collect();
+                    return Err(ScheduleError::Unfilled(unfilled));
+                }
+            }
+        }
+        self.calendar = calendar;
+        self.availabilities = availabilities;
+        Ok(())
+    }
+
+    /// Alternative to `make_calendar`, for organisations with a dedicated pool of persons for each event: each event
+    /// is solved on its own, only with the persons available at least once for it, and the four calendars are merged.
+    /// When a person is allocated to two events of the same day, the first event in the order First daily, First
+    /// nightly, Second daily, Second nightly keeps her, except for the Second level on friday, saturday and sunday.
+    /// No subcontractor is added. Return the days / events left empty.
+    pub fn solve_events_independently(&mut self) -> Result<(), ScheduleError> {
+        self.calendar_made = true;
+        let availabilities = self.qualified_availabilities();
+        let mut calendar = self.calendar.clone();
+        for event in Event::ALL {
+            let pool = availabilities
+                .iter()
+                .filter(|(_, her_availabilities)| {
+                    her_availabilities
+                        .get_all()
+                        .values()
+                        .any(|events| events.contains(&event))
+                })
+                .map(|(name, her_availabilities)| (name.clone(), her_availabilities.clone()))
+                .collect::<AvailabilitiesPerPerson>();
+            let (event_calendar, _, _) = self.make_calendar_for_event(&self.calendar, &pool, event);
+            for (day, events) in event_calendar.get_all() {
+                let Some(name) = events.get(&event) else {
+                    continue;
+                };
+                let is_conflicting =
+                    calendar
+                        .events_on_day(day)
+                        .unwrap()
+                        .iter()
+                        .any(|(other_event, other_name)| {
+                            other_name == name
+                                && *other_event != event
+                                && !(Self::is_second_level_on_weekend(*day, event)
+                                    && Self::is_second_level_on_weekend(*day, *other_event))
+                        });
+                if !is_conflicting {
+                    calendar.set_for(*day, event, name.clone());
+                }
+            }
+        }
+        let empty_slots = Event::ALL
+            .iter()
+            .flat_map(|event| {
+                calendar
+                    .get_empty_days(event)
+                    .into_iter()
+                    .map(|day| (day, *event))
+            })
+            .sorted()
+            .collect::<Vec<(Date, Event)>>();
+        if !empty_slots.is_empty() {
+            return Err(ScheduleError::Unfilled(empty_slots));
+        }
+        self.availabilities = self.availabilities_for(&calendar);
+        self.calendar = calendar;
+        Ok(())
+    }
+
+    /// Return the events sorted by the average number of persons available per day, the most constrained first.
+    pub fn optimize_event_order(&self) -> [Event; 4] {
+        let availabilities = self.qualified_availabilities();
+        let mut events = Event::ALL;
+        // Comparing the total is the same as comparing the average, as all the events have the same number of days
+        events.sort_by_cached_key(|event| {
+            self.calendar
+                .days()
+                .map(|day| Self::persons_available(&availabilities, &day, *event).len())
+                .sum::<usize>()
+        });
+        events
+    }
+
+    /// Return the availabilities, without the events the persons are not senior enough or not qualified for.
+    fn qualified_availabilities(&self) -> AvailabilitiesPerPerson {
+        let mut availabilities = self.availabilities.clone();
+        for (name, events) in &self.qualified_events {
+            if let Some(her_availabilities) = availabilities.get_mut(name) {
+                her_availabilities.restrict_to_events(events);
+            }
+        }
+        for (event, min_seniority) in &self.min_seniority_per_event {
+            for (name, her_availabilities) in availabilities.iter_mut() {
+                let is_qualified = self
+                    .persons
+                    .get(name)
+                    .is_none_or(|person| person.seniority() >= *min_seniority);
+                if !is_qualified {
+                    her_availabilities.pop_event_for_all_days(*event);
+                }
+            }
+        }
+        for (day, events) in self.calendar.get_all() {
+            for (event, name) in events {
+                if let Some(her_availabilities) = availabilities.get_mut(name) {
+                    self.apply_optional_rules(her_availabilities, *day, *event);
+                }
+            }
+        }
+        availabilities
+    }
+
+    /// Update the availabilities of a person on-call for this day and event according to the rules enabled on top of
+    /// `Availabilities::update_availabilities`.
+    fn apply_optional_rules(
+        &self,
+        her_availabilities: &mut Availabilities,
+        day: Date,
+        event: Event,
+    ) {
+        self.block_event_groups(her_availabilities, day, event);
+        self.block_rest_after_night(her_availabilities, day, event);
+        self.block_holiday_rest(her_availabilities, day);
+        if self.avoid_same_day_double {
+            let other_event = match event {
+                Event::FirstDaily => Event::FirstNightly,
+                Event::FirstNightly => Event::FirstDaily,
+                Event::SecondDaily => Event::SecondNightly,
+                Event::SecondNightly => Event::SecondDaily,
+            };
+            her_availabilities.pop_event(&day, other_event);
+        }
+    }
+
+    /// Remove the events of the next day starting less than the minimum rest after a nightly event, or the nightly
+    /// events of the previous day ending less than the minimum rest before this event, from the availabilities of a
+    /// person on-call for this day and event.
+    fn block_rest_after_night(
+        &self,
+        her_availabilities: &mut Availabilities,
+        day: Date,
+        event: Event,
+    ) {
+        // Number of hours between the end of the nightly events of the previous day and the start of an event
+        let hours_after_night = |event: Event| {
+            if event.is_daily() {
+                0
+            } else {
+                DAILY_DURATION_HOURS as u16
+            }
+        };
+        let is_too_close = |event: Event| hours_after_night(event) < self.rest_hours_after_night;
+        let events = Event::ALL;
+        if let Some(next_day) = day.next_day().filter(|_| event.is_nightly()) {
+            for next_event in events.into_iter().filter(|e| is_too_close(*e)) {
+                her_availabilities.pop_event(&next_day, next_event);
+            }
+        }
+        if let Some(previous_day) = day.previous_day().filter(|_| is_too_close(event)) {
+            for previous_event in events.into_iter().filter(Event::is_nightly) {
+                her_availabilities.pop_event(&previous_day, previous_event);
+            }
+        }
+    }
+
+    /// Remove the two days following this day if it's a public holiday, and the public holidays among the two days
+    /// preceding it, from the availabilities of a person on-call for this day.
+    fn block_holiday_rest(&self, her_availabilities: &mut Availabilities, day: Date) {
+        for offset in 1..=2 {
+            let duration = time::Duration::days(offset);
+            if self.holidays.contains(&day) {
+                her_availabilities.pop_all(&(day + duration));
+            }
+            if self.holidays.contains(&(day - duration)) {
+                her_availabilities.pop_all(&(day - duration));
+            }
+        }
+    }
+
+    /// Remove the events grouped with `event` on the previous and the next day from the availabilities of a person
+    /// on-call for this day and event.
+    fn block_event_groups(&self, her_availabilities: &mut Availabilities, day: Date, event: Event) {
+        for group in self
+            .event_groups
+            .iter()
+            .filter(|group| group.contains(&event))
+        {
+            for adjacent_day in [day.previous_day(), day.next_day()].into_iter().flatten() {
+                for grouped_event in group {
+                    her_availabilities.pop_event(&adjacent_day, *grouped_event);
+                }
+            }
+        }
+    }
+
+    fn make_calendar_for_event(
+        &self,
+        calendar: &Calendar,
+        availabilities: &AvailabilitiesPerPerson,
+        event: Event,
+    ) -> (Calendar, AvailabilitiesPerPerson, Option<Date>) {
+        let (new_availabilities, new_calendar, problematic_day, _) = self.find_next(
+            availabilities.clone(),
+            calendar.clone(),
+            Self::on_call_counts(calendar),
+            event,
+            0,
+        );
+        if new_calendar.get_empty_days(&event).is_empty() {
+            return (new_calendar, new_availabilities, None);
+        }
+        (calendar.clone(), availabilities.clone(), problematic_day)
+    }
+
+    pub fn calendar(&self) -> &Calendar {
+        &self.calendar
+    }
+
+    /// Return the persons on-call for `event`, in date order, skipping the empty days. E.g. to continue the rotation
+    /// of this calendar with `solve_with_fixed_rotation` for the next period.
+    pub fn export_rotation(&self, event: Event) -> Vec<Name> {
+        self.calendar
+            .get_all()
+            .values()
+            .filter_map(|events| events.get(&event).cloned())
+            .collect()
+    }
+
+    pub fn calendar_as_string(&self) -> String {
+        self.calendar.to_string()
+    }
+
+    /// Return the current availabilities in the same CSV format as the input: a header row with the month, the year
+    /// and the days, then one row per person and per event, with 'x' when not available and an empty cell otherwise.
+    pub fn export_availability_csv(&self) -> String {
+        let mut csv = csv_header(&self.calendar);
+        for (name, her_availabilities) in
+            self.availabilities.iter().sorted_by_key(|(name, _)| *name)
+        {
+            for event in Event::ALL {
+                csv.push_str(&format!("{},{}", name, event.to_label(Locale::default())));
+                for day in self.calendar.days() {
+                    let is_available = her_availabilities
+                        .get(&day)
+                        .is_some_and(|events| events.contains(&event));
+                    csv.push_str(if is_available { "," } else { ",x" });
+                }
+                csv.push_str("\r\n");
+            }
+        }
+        csv
+    }
+
+    /// Return a CSV file to fill in, from `from` to `to`, with one row for each person and event passed in argument.
+    /// All the cells are empty, i.e. the persons are available every day: put an `x` where they are not.
+    pub fn generate_example_csv(from: Date, to: Date, persons: &[(&str, &[Event])]) -> String {
+        let calendar = Calendar::new(from, to);
+        let mut csv = csv_header(&calendar);
+        for (name, events) in persons {
+            for event in *events {
+                csv.push_str(&format!("{},{}", name, event.to_label(Locale::default())));
+                csv.push_str(&",".repeat(calendar.day_count()));
+                csv.push_str("\r\n");
+            }
+        }
+        csv
+    }
+
+    /// Return a grid with one row per person and one column per day, showing how many events the person is available
+    /// for that day: '·' if none, '1' to '4' otherwise. The days with few persons available stand out before solving.
+    pub fn availability_heatmap(&self) -> String {
+        let names = self.persons();
+        let name_width = names.iter().map(|name| name.len()).max().unwrap_or(0);
+        let header = format!(
+            "{:<width$} |{}",
+            "",
+            self.calendar.days().fold(String::new(), |acc, day| acc
+                + &format!(" {:0>2}", day.day())),
+            width = name_width
+        );
+        let mut s = format!("{}\r\n{}\r\n", header, "-".repeat(header.chars().count()));
+        for name in names {
+            let her_availabilities = self.availabilities.get(name).unwrap();
+            s.push_str(&format!("{:<width$} |", name, width = name_width));
+            for day in self.calendar.days() {
+                match her_availabilities
+                    .get(&day)
+                    .map_or(0, |events| events.len())
+                {
+                    0 => s.push_str("  ·"),
+                    count => s.push_str(&format!("  {}", count)),
+                }
+            }
+            s.push_str("\r\n");
+        }
+        s
+    }
+
+    /// Print, for each person, the number of on-calls per event, the total, and the deviation from the mean total.
+    /// Then warn about the persons on-call far more often than the others, see `check_event_distribution`.
+    pub fn print_statistics(&self) {
+        print!("{}", self.format_statistics());
+    }
+
+    /// Summarize the quality of the calendar in a few sentences, e.g. for a management report: how many days / events
+    /// are filled, the subcontractors on-call, the fairness score, qualified as good up to 1 and fair up to 2, and the
+    /// most and least loaded persons.
+    pub fn describe_solution_quality(&self) -> String {
+        let plural = |count: usize| if count == 1 { "" } else { "s" };
+        let day_count = self.calendar.day_count();
+        let slot_count = day_count * 4;
+        let empty_slot_count = self.empty_slots().len();
+        let mut s = if empty_slot_count == 0 {
+            format!(
+                "The schedule covers all {} days / events across {} day{}.",
+                slot_count,
+                day_count,
+                plural(day_count)
+            )
+        } else {
+            format!(
+                "The schedule covers {} of the {} days / events across {} day{}, {} left empty.",
+                slot_count - empty_slot_count,
+                slot_count,
+                day_count,
+                plural(day_count),
+                empty_slot_count
+            )
+        };
+        let subco_allocations = self
+            .calendar
+            .get_all()
+            .iter()
+            .flat_map(|(day, events)| {
+                events
+                    .iter()
+                    .sorted()
+                    .map(move |(event, name)| (name, *day, *event))
+            })
+            .filter(|(name, _, _)| {
+                self.persons
+                    .get(*name)
+                    .is_some_and(Person::is_subcontractor)
+            })
+            .collect::<Vec<(&Name, Date, Event)>>();
+        let subco_count = subco_allocations
+            .iter()
+            .map(|(name, _, _)| name)
+            .unique()
+            .count();
+        let subco_allocations = subco_allocations
+            .iter()
+            .map(|(name, day, event)| format!("{} on {} / {:?}", name, day, event))
+            .join(", ");
+        match subco_count {
+            0 => s.push_str(" No subcontractor was required."),
+            1 => s.push_str(&format!(
+                " 1 subcontractor was required ({}).",
+                subco_allocations
+            )),
+            _ => s.push_str(&format!(
+                " {} subcontractors were required ({}).",
+                subco_count, subco_allocations
+            )),
+        }
+        let fairness = self.fairness_score(&self.calendar);
+        let quality = match fairness {
+            f if f <= 1.0 => "good",
+            f if f <= 2.0 => "fair",
+            _ => "poor",
+        };
+        s.push_str(&format!(
+            " The fairness score is {:.2} ({}).",
+            fairness, quality
+        ));
+        let (counts, mean, std_dev) = self.on_call_count_distribution(&self.calendar);
+        // On ties, the first one in alphabetical order
+        let most_loaded = counts
+            .iter()
+            .min_by_key(|(name, count)| (std::cmp::Reverse(*count), *name));
+        let least_loaded = counts.iter().min_by_key(|(name, count)| (*count, *name));
+        if let (Some((most, most_count)), Some((least, least_count))) = (most_loaded, least_loaded)
+        {
+            s.push_str(&format!(
+                " The most overloaded person is {} with {} assignment{} (mean: {:.1}, std dev: {:.1}).",
+                most,
+                most_count,
+                plural(*most_count),
+                mean,
+                std_dev
+            ));
+            s.push_str(&format!(
+                " The least loaded is {} with {} assignment{}.",
+                least,
+                least_count,
+                plural(*least_count)
+            ));
+        }
+        s
+    }
+
+    fn format_statistics(&self) -> String {
+        let events = Event::ALL;
+        let names = self.persons();
+        let name_width = names
+            .iter()
+            .map(|name| name.len())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+        let mut counts = HashMap::new();
+        for day_events in self.calendar.get_all().values() {
+            for (event, name) in day_events {
+                *counts.entry((name.as_str(), *event)).or_insert(0) += 1;
+            }
+        }
+        let total_for = |name: &str| {
+            events
+                .iter()
+                .map(|event| counts.get(&(name, *event)).unwrap_or(&0))
+                .sum::<usize>()
+        };
+        let mean = names.iter().map(|name| total_for(name)).sum::<usize>() as f64
+            / names.len().max(1) as f64;
+
+        let header = format!(
+            "{:<width$} |{} Total | Deviation",
+            "Name",
+            events
+                .iter()
+                .fold(String::new(), |acc, e| acc + &format!("  {}  |", e)),
+            width = name_width
+        );
+        let mut s = format!("{}\r\n{}\r\n", header, "-".repeat(header.len()));
+        for name in names {
+            s.push_str(&format!("{:<width$} |", name, width = name_width));
+            for event in &events {
+                s.push_str(&format!(
+                    " {:>3} |",
+                    counts.get(&(name, *event)).unwrap_or(&0)
+                ));
+            }
+            let total = total_for(name);
+            s.push_str(&format!(
+                " {:>5} | {:>+9.1}\r\n",
+                total,
+                total as f64 - mean
+            ));
+        }
+        for (name, count, z_score) in self.check_event_distribution() {
+            s.push_str(&format!(
+                "Warning: {} is on-call {} times, far above the others (z-score {:.1})\r\n",
+                name, count, z_score
+            ));
+        }
+        s
+    }
+
+    /// Designate `backup` as the person to escalate to, whenever `primary` is on-call for `event`.
+    pub fn add_on_call_chain(&mut self, primary: &str, backup: &str, event: Event) {
+        self.on_call_chains
+            .push((primary.to_string(), backup.to_string(), event));
+    }
+
+    /// Check the on-call chains against the calendar, and return the violations.
+    /// The backup can't be the primary herself, and must not be on-call for any event of the day the primary is on-call.
+    pub fn validate_on_call_chain(&self) -> Vec<(Date, Event, String)> {
+        let mut violations = Vec::new();
+        for (primary, backup, event) in &self.on_call_chains {
+            for (day, _) in self
+                .calendar
+                .assignments_for_person(primary)
+                .into_iter()
+                .filter(|(_, e)| e == event)
+            {
+                if primary == backup {
+                    violations.push((day, *event, format!("{} is her own backup", primary)));
+                    continue;
+                }
+                let events_of_the_day = self.calendar.events_on_day(&day).unwrap();
+                for (backup_event, _) in events_of_the_day
+                    .iter()
+                    .filter(|(_, name)| *name == backup)
+                    .sorted()
+                {
+                    violations.push((
+                        day,
+                        *event,
+                        format!(
+                            "{}, backup of {}, is also on-call for {:?}",
+                            backup, primary, backup_event
+                        ),
+                    ));
+                }
+            }
+        }
+        violations.sort();
+        violations
+    }
+
+    /// Panic if a person is on-call for two events of the same day, except for the Second level on friday, saturday
+    /// and sunday. Only checked in debug builds, as a post-condition of the solver.
+    pub fn assert_no_double_booking(&self) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        for (day, events) in self.calendar.get_all() {
+            let mut events_per_person: HashMap<&Name, Vec<Event>> = HashMap::new();
+            for (event, name) in events {
+                events_per_person.entry(name).or_default().push(*event);
+            }
+            for (name, events) in events_per_person {
+                let is_allowed = events.len() == 1
+                    || events
+                        .iter()
+                        .all(|event| Self::is_second_level_on_weekend(*day, *event));
+                assert!(
+                    is_allowed,
+                    "{} is on-call for {:?} on {}",
+                    name,
+                    events.iter().sorted().collect::<Vec<&Event>>(),
+                    day
+                );
+            }
+        }
+    }
+
+    /// Return the persons on-call more than the mean plus twice the standard deviation, which may be unfair,
+    /// with their number of on-calls and their z-score.
+    pub fn check_event_distribution(&self) -> Vec<(Name, usize, f64)> {
+        let (counts, mean, stddev) = self.on_call_count_distribution(&self.calendar);
+        counts
+            .into_iter()
+            .filter(|(_, count)| stddev > 0.0 && *count as f64 > mean + 2.0 * stddev)
+            .map(|(name, count)| (name.to_string(), count, (count as f64 - mean) / stddev))
+            .collect()
+    }
+
+    /// Return the number of on-calls in the calendar of each person, with their mean and standard deviation.
+    fn on_call_count_distribution(&self, calendar: &Calendar) -> (Vec<(&str, usize)>, f64, f64) {
+        let counts = calendar.on_call_count_per_person();
+        let counts = self
+            .persons()
+            .into_iter()
+            .map(|name| (name, counts.get(&name.to_string()).copied().unwrap_or(0)))
+            .collect::<Vec<(&str, usize)>>();
+        if counts.is_empty() {
+            return (counts, 0.0, 0.0);
+        }
+        let mean = counts.iter().map(|(_, count)| *count as f64).sum::<f64>() / counts.len() as f64;
+        let variance = counts
+            .iter()
+            .map(|(_, count)| (*count as f64 - mean).powi(2))
+            .sum::<f64>()
+            / counts.len() as f64;
+        (counts, mean, variance.sqrt())
+    }
+
+    /// Check that nobody is on-call for two events of the same day or of two consecutive days, except for the Second
+    /// level on friday, saturday and sunday. Return the second on-call of each pair breaking the rule.
+    pub fn check_consecutive_day_rule_violations(&self) -> Vec<(Date, Event, Name)> {
+        let mut violations = Vec::new();
+        for name in self.calendar.on_call_count_per_person().keys() {
+            let assignments = self.calendar.assignments_for_person(name);
+            for (i, &(day, event)) in assignments.iter().enumerate() {
+                let breaks_the_rule =
+                    assignments[..i]
+                        .iter()
+                        .any(|&(previous_day, previous_event)| {
+                            previous_day.days_until(day) <= 1
+                                && !(Self::is_second_level_on_weekend(day, event)
+                                    && Self::is_second_level_on_weekend(
+                                        previous_day,
+                                        previous_event,
+                                    ))
+                        });
+                if breaks_the_rule {
+                    violations.push((day, event, name.to_string()));
+                }
+            }
+        }
+        violations.sort();
+        violations
+    }
+
+    /// Return the person, the first and the last day of each period of more than `max_gap` days in a row during
+    /// which she is available but not on-call, sorted. The days she isn't available for anything end the period.
+    pub fn check_max_gap(&self, max_gap: u8) -> Vec<(Name, Date, Date)> {
+        let mut gaps = Vec::new();
+        for (name, her_availabilities) in &self.initial_availabilities {
+            let on_call_days = self
+                .calendar
+                .assignments_for_person(name)
+                .into_iter()
+                .map(|(day, _)| day)
+                .collect::<Vec<Date>>();
+            let is_idle = |day: &Date| {
+                !on_call_days.contains(day)
+                    && her_availabilities
+                        .get(day)
+                        .is_some_and(|events| !events.is_empty())
+            };
+            let days = self.calendar.days().collect::<Vec<Date>>();
+            for (is_idle, run) in &days.iter().chunk_by(|day| is_idle(day)) {
+                let run = run.collect::<Vec<&Date>>();
+                if is_idle && run.len() > max_gap as usize {
+                    gaps.push((name.clone(), *run[0], *run[run.len() - 1]));
+                }
+            }
+        }
+        gaps.sort();
+        gaps
+    }
+
+    /// Return one iCalendar (.ics content) per person, containing only the events this person is on-call for.
+    pub fn export_to_ical_per_person(&self) -> HashMap<Name, String> {
+        self.availabilities
+            .keys()
+            .map(|name| {
+                let assignments = self.calendar.assignments_for_person(name);
+                (name.clone(), ical::person_calendar(name, &assignments))
+            })
+            .collect()
+    }
+
+    /// Return why the subcontractor `EXT-<subco_index>` was added by the last call to `make_calendar`: who was
+    /// still available for the day / event given to her, and who wasn't anymore because of her other on-calls.
+    pub fn explain_subcontractor_need(&self, subco_index: u8) -> String {
+        match self.subco_explanations.get(subco_index as usize) {
+            Some(explanation) => explanation.to_string(),
+            None => format!("{}{} was not added", SUBCONTRACTOR_PREFIX, subco_index),
+        }
+    }
+
+    /// Tell who was available for the day and event in the best calendar of a failed search.
+    fn explain_subco(
+        &self,
+        subco_name: &str,
+        day: Date,
+        event: Event,
+        partial: &Calendar,
+    ) -> SubcoExplanation {
+        let availabilities = self.qualified_availabilities();
+        let mut remaining = availabilities.clone();
+        for (on_call_day, events) in partial.get_all() {
+            for (on_call_event, name) in events {
+                if let Some(her_availabilities) = remaining.get_mut(name) {
+                    Availabilities::update_availabilities(
+                        her_availabilities,
+                        *on_call_day,
+                        *on_call_event,
+                    );
+                }
+            }
+        }
+        let available = Self::persons_available(&remaining, &day, event);
+        let blocked_by = Self::persons_available(&availabilities, &day, event)
+            .into_iter()
+            .filter(|name| !available.contains(name))
+            .cloned()
+            .collect();
+        SubcoExplanation {
+            name: subco_name.to_string(),
+            day,
+            event,
+            available: available.len(),
+            blocked_by,
+        }
+    }
+
+    /// Add the i-th subcontractor for the day and event passed in argument, explaining why she's needed.
+    /// Return her name.
+    fn call_subco(&mut self, i: u8, day: Date, event: Event, partial: &Calendar) -> Name {
+        let subco_name = format!("{}{}", SUBCONTRACTOR_PREFIX, i);
+        let explanation = self.explain_subco(&subco_name, day, event, partial);
+        self.subco_explanations.push(explanation);
+        self.add_subco(&subco_name, day, event);
+        if self.verbose {
+            println!(
+                "Adding {} for {} / {:?}",
+                self.persons[&subco_name], day, event
+            );
+        }
+        subco_name
+    }
+
+    /// Add a subcontractor to the current and initial availabilities, available only for the day and event passed
+    /// in argument.
+    fn add_subco(&mut self, subco_name: &str, subco_day: Date, event: Event) {
+        self.persons
+            .entry(subco_name.to_string())
+            .or_insert_with(|| Person::new_subcontractor(subco_name));
+        self.availabilities = self.add_subco_for_this_day_and_event(
+            &self.availabilities.clone(),
+            subco_name,
+            subco_day,
+            event,
+        );
+        self.initial_availabilities = self.add_subco_for_this_day_and_event(
+            &self.initial_availabilities.clone(),
+            subco_name,
+            subco_day,
+            event,
+        );
+    }
+
+    /// Add a subcontractor for the day and event passed in argument.
+    fn add_subco_for_this_day_and_event(
+        &self,
+        availabilities: &HashMap<String, Availabilities>,
+        subco_name: &str,
+        subco_day: Date,
+        event: Event,
+    ) -> AvailabilitiesPerPerson {
+        let mut availabilities_str = event.to_label(Locale::default()).to_string();
+        for day in self.calendar.days() {
+            availabilities_str.push_str(if day == subco_day { "," } else { ",x" });
+        }
+        let (from, to) = (self.calendar.from(), self.calendar.to());
+        let mut new_availabilities = availabilities.clone();
+        new_availabilities
+            .entry(subco_name.to_owned())
+            .and_modify(|a| {
+                a.merge(from, to, &availabilities_str);
+            })
+            .or_insert_with(|| Availabilities::from_str(from, to, &availabilities_str).0);
+        new_availabilities
+    }
+
+    /// Recursive function to find the next person for the next empty day
+    /// `on_call_counts` is the number of days each person is on-call in `calendar`, updated along the recursion.
+    fn find_next(
+        &self,
+        availabilities: AvailabilitiesPerPerson,
+        calendar: Calendar,
+        on_call_counts: HashMap<Name, usize>,
+        event: Event,
+        recursion_depth: u16,
+    ) -> (AvailabilitiesPerPerson, Calendar, Option<Date>, u16) {
+        let availabilities = availabilities.clone();
+        let calendar = calendar.clone();
+        let mut problematic_day = None;
+        let remaining_days = calendar.get_empty_days(&event);
+        if !remaining_days.is_empty() {
+            let mut days_and_names =
+                Self::get_days_with_least_availabilities(&availabilities, &remaining_days, event);
+            if self.scarce_persons_first {
+                days_and_names = Self::scarcest_first(&availabilities, days_and_names);
+            }
+            days_and_names =
+                self.put_first_day_first(&availabilities, &remaining_days, event, days_and_names);
+            // Check for premature stop, if there's 2 consecutive days with only the same person available
+            if Self::check_for_premature_stop(&days_and_names, &event) {
+                return (
+                    availabilities,
+                    calendar,
+                    problematic_day,
+                    recursion_depth + 1,
+                );
+            }
+            // Each day is tried in turn as the next one to fill, the recursion takes care of the other days,
+            // so trying the other orders of these days would only explore the same calendars again
+            for (day, names) in &days_and_names {
+                problematic_day = Some(*day);
+                if names.is_empty() {
+                    // No more possibilities, return the current state
+                    return (
+                        availabilities,
+                        calendar,
+                        problematic_day,
+                        recursion_depth + 1,
+                    );
+                }
+                // println!(
+                //     "Recursion depth: {}, Event: {:?}, Day: {}, Names: {:?}",
+                //     recursion_depth, event, day, names
+                // );
+                let mut sorted_names =
+                    self.sort_names(names, &on_call_counts, &calendar, *day, event);
+                if self.scarce_persons_first {
+                    // Stable, the balancing strategy still orders the persons as scarce
+                    sorted_names.sort_by_cached_key(|name| Self::slot_count(&availabilities, name));
+                }
+                // Every name is tried in turn when the recursion fails with the previous one
+                for name in &sorted_names {
+                    if !self.is_within_max_hours_per_week(&calendar, name, *day, event)
+                        || !self.is_within_contract(&calendar, name, *day, event)
+                    {
+                        continue;
+                    }
+                    let mut new_calendar = calendar.clone();
+                    let mut new_availabilities = availabilities.clone();
+                    let mut new_on_call_counts = on_call_counts.clone();
+                    let new_recursion_depth;
+                    // Set the person for this day, and update her availabilities
+                    if !Self::is_on_call(calendar.events_on_day(day).unwrap(), name) {
+                        *new_on_call_counts.entry(name.clone()).or_insert(0) += 1;
+                    }
+                    new_calendar.set_for(*day, event, name.clone());
+                    let her_availabilities = new_availabilities.get_mut(name).unwrap();
+                    Availabilities::update_availabilities(her_availabilities, *day, event);
+                    self.apply_optional_rules(her_availabilities, *day, event);
+                    // Continue to find the next person for the next day
+                    (
+                        new_availabilities,
+                        new_calendar,
+                        problematic_day,
+                        new_recursion_depth,
+                    ) = self.find_next(
+                        new_availabilities,
+                        new_calendar,
+                        new_on_call_counts,
+                        event,
+                        recursion_depth + 1,
+                    );
+                    // Successful end condition is reached, return the result
+                    if new_calendar.get_empty_days(&event).is_empty() {
+                        return (new_availabilities, new_calendar, None, new_recursion_depth);
+                    }
+                }
+            }
+        }
+        (availabilities, calendar, problematic_day, recursion_depth)
+    }
+
+    /// Return true if the person designated by `name` can be on-call for this day and event without exceeding her
+    /// maximal number of hours, or the one of everybody, in the ISO week of that day.
+    fn is_within_max_hours_per_week(
+        &self,
+        calendar: &Calendar,
+        name: &Name,
+        day: Date,
+        event: Event,
+    ) -> bool {
+        let her_max_hours = self
+            .persons
+            .get(name)
+            .and_then(|person| person.max_hours_per_week())
+            .map(u16::from);
+        match her_max_hours
+            .into_iter()
+            .chain(self.max_weekly_oncall_hours)
+            .min()
+        {
+            Some(max_hours) => {
+                calendar.hours_in_week_for_person(name, day) + event.duration_hours() as u16
+                    <= max_hours
+            }
+            None => true,
+        }
+    }
+
+    /// Return true if the person designated by `name` can be on-call for this day and event without exceeding her
+    /// maximal number of hours in the month of that day, nor resting less than her minimum between two events.
+    fn is_within_contract(
+        &self,
+        calendar: &Calendar,
+        name: &Name,
+        day: Date,
+        event: Event,
+    ) -> bool {
+        let Some(person) = self.persons.get(name) else {
+            return true;
+        };
+        if let Some(max_hours) = person.max_hours_per_month() {
+            if calendar.hours_in_month_for_person(name, day) + event.duration_hours() as u16
+                > max_hours
+            {
+                return false;
+            }
+        }
+        let Some(min_rest) = person.min_rest_hours() else {
+            return true;
+        };
+        // Start and end of an event, in hours since an arbitrary origin
+        let hours = |day: Date, event: Event| {
+            let start_hour = if event.is_daily() {
+                ical::DAILY_START_HOUR
+            } else {
+                ical::NIGHTLY_START_HOUR
+            };
+            let start = day.to_julian_day() as i64 * 24 + start_hour as i64;
+            (start, start + event.duration_hours() as i64)
+        };
+        let (start, end) = hours(day, event);
+        calendar
+            .assignments_for_person(name)
+            .into_iter()
+            .all(|(other_day, other_event)| {
+                let (other_start, other_end) = hours(other_day, other_event);
+                let rest = (start - other_end).max(other_start - end);
+                rest >= min_rest as i64
+            })
+    }
+
+    /// Sort the names in the order they should be tried for this day and event, according to the balancing strategy.
+    fn sort_names(
+        &self,
+        names: &[Name],
+        on_call_counts: &HashMap<Name, usize>,
+        calendar: &Calendar,
+        day: Date,
+        event: Event,
+    ) -> Vec<Name> {
+        // The sorts below are stable, so ordering the names by the tie-breaking strategy first makes it the
+        // secondary key
+        let names = &self.break_ties(names, calendar, day);
+        let names = match self.balancing_strategy {
+            BalancingStrategy::LeastOverall => {
+                self.sort_names_by_least_on_call(names, on_call_counts)
+            }
+            BalancingStrategy::LeastForEvent => names
+                .iter()
+                .sorted_by_key(|name| {
+                    calendar
+                        .get_all()
+                        .values()
+                        .filter(|events| events.get(&event) == Some(*name))
+                        .count()
+                })
+                .cloned()
+                .collect(),
+            BalancingStrategy::RoundRobin => {
+                let all_names = self.persons();
+                let previous_name = calendar
+                    .get_all()
+                    .range(..day)
+                    .rev()
+                    .find_map(|(_, events)| events.get(&event));
+                let start = previous_name
+                    .and_then(|previous| all_names.iter().position(|name| name == previous))
+                    .map_or(0, |position| position + 1);
+                names
+                    .iter()
+                    .sorted_by_key(|name| {
+                        let position = all_names.iter().position(|n| n == name).unwrap_or(0);
+                        (position + all_names.len() - start) % all_names.len()
+                    })
+                    .cloned()
+                    .collect()
+            }
+            BalancingStrategy::Priority => self
+                .sort_names_by_least_on_call(names, on_call_counts)
+                .into_iter()
+                .sorted_by_key(|name| {
+                    std::cmp::Reverse(self.persons.get(name).map_or(0, Person::preference_weight))
+                })
+                .collect(),
+            BalancingStrategy::CarryOver => {
+                let on_call_counts = names
+                    .iter()
+                    .map(|name| {
+                        let count = on_call_counts.get(name).copied().unwrap_or(0);
+                        let imported = self.imported_balance.get(name).copied().unwrap_or(0);
+                        (name.clone(), count + imported)
+                    })
+                    .collect();
+                self.sort_names_by_least_on_call(names, &on_call_counts)
+            }
+        };
+        let names = match self.max_gap {
+            Some(max_gap) => names
+                .into_iter()
+                .sorted_by_cached_key(|name| !Self::is_gap_too_long(calendar, name, day, max_gap))
+                .collect(),
+            None => names,
+        };
+        let needs_senior = self.senior_night_pairing
+            && event.is_nightly()
+            && !calendar
+                .events_on_day(&day)
+                .is_some_and(|events| events.values().any(|name| self.is_senior(name)));
+        if needs_senior {
+            return names
+                .into_iter()
+                .sorted_by_cached_key(|name| !self.is_senior(name))
+                .collect();
+        }
+        names
+    }
+
+    /// Return true if the person designated by `name` isn't on-call for more than `max_gap` days in a row around
+    /// this day, this day included, in the calendar.
+    fn is_gap_too_long(calendar: &Calendar, name: &Name, day: Date, max_gap: u8) -> bool {
+        let is_on_call = |events: &HashMap<Event, Name>| events.values().any(|n| n == name);
+        let previous = calendar
+            .get_all()
+            .range(..day)
+            .rev()
+            .find(|(_, events)| is_on_call(events))
+            .map_or(calendar.from().previous_day().unwrap(), |(day, _)| *day);
+        let next = calendar
+            .get_all()
+            .range(day.next_day().unwrap()..)
+            .find(|(_, events)| is_on_call(events))
+            .map_or(calendar.to().next_day().unwrap(), |(day, _)| *day);
+        previous.days_until(next) - 1 > max_gap as i64
+    }
+
+    /// Order the names according to the tie-breaking strategy.
+    fn break_ties(&self, names: &[Name], calendar: &Calendar, day: Date) -> Vec<Name> {
+        let last_assigned = |name: &Name| {
+            calendar
+                .get_all()
+                .iter()
+                .rev()
+                .find(|(_, events)| events.values().any(|n| n == name))
+                .map(|(day, _)| *day)
+        };
+        let mut names = names.iter().cloned().sorted().collect::<Vec<Name>>();
+        match self.tie_breaking_strategy {
+            TieBreakingStrategy::Alphabetical => {}
+            TieBreakingStrategy::LastAssignedFirst => {
+                names.sort_by_cached_key(|name| std::cmp::Reverse(last_assigned(name)))
+            }
+            TieBreakingStrategy::LastAssignedLast => names.sort_by_cached_key(last_assigned),
+            TieBreakingStrategy::Random(_) if self.seed.is_some() => {
+                names.shuffle(&mut *self.rng.borrow_mut())
+            }
+            TieBreakingStrategy::Random(seed) => {
+                // Another order for each day, but always the same for a given seed
+                let mut rng =
+                    SmallRng::seed_from_u64(seed.wrapping_add(day.to_julian_day() as u64));
+                names.shuffle(&mut rng);
+            }
+        }
+        names
+    }
+
+    /// Sort the names by the least on-call days, allow to balance the on-call days between all the persons
+    /// When the employees are tried first, a subcontractor counts as on-call one more day, and comes after the
+    /// employees on-call as often.
+    fn sort_names_by_least_on_call(
+        &self,
+        names: &[Name],
+        on_call_counts: &HashMap<Name, usize>,
+    ) -> Vec<Name> {
+        names
+            .iter()
+            .sorted_by_key(|n| {
+                let count = on_call_counts.get(*n).copied().unwrap_or(0);
+                let is_subco = self.employees_first
+                    && self.persons.get(*n).is_some_and(Person::is_subcontractor);
+                (count + is_subco as usize, is_subco)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Return the number of days each person is on-call in the calendar.
+    fn on_call_counts(calendar: &Calendar) -> HashMap<Name, usize> {
+        let mut on_call_counts = HashMap::new();
+        for events in calendar.get_all().values() {
+            for name in events.values().unique() {
+                *on_call_counts.entry(name.clone()).or_insert(0) += 1;
+            }
+        }
+        on_call_counts
+    }
+
+    /// Return true if the person designated by `name` is on call in one of the event passed in argument `availabilities`
+    fn is_on_call(availabilities: &HashMap<Event, Name>, name: &Name) -> bool {
+        for event in Event::ALL {
+            if let Some(on_call) = availabilities.get(&event) {
+                if name == on_call {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Return true if there's 2 consecutive week days with only the same person available
+    fn check_for_premature_stop(days_and_names: &[(Date, Vec<Name>)], event: &Event) -> bool {
+        if days_and_names.len() < 2 {
+            return false;
+        }
+        for i in 0..days_and_names.len() - 1 {
+            // continue if there's more than one person available
+            if days_and_names[i].1.len() != 1 {
+                continue;
+            }
+            // Continue if one of the day is a week-end, and we're searching a person available for a Second level event
+            let one_of_the_day_is_weekend =
+                Self::is_weekend(days_and_names[i].0) || Self::is_weekend(days_and_names[i + 1].0);
+            if one_of_the_day_is_weekend && event.is_second_level() {
+                continue;
+            }
+            // Return true if there's 2 consecutive days with only the same person available
+            let are_consecutive_days = days_and_names[i]
                 .0
-                .ordinal()
-                .abs_diff(days_and_names[i + 1].0.ordinal())
+                .days_until(days_and_names[i + 1].0)
+                .abs()
                 == 1;
             let is_same_person = days_and_names[i].1 == days_and_names[i + 1].1;
             if are_consecutive_days && is_same_person {
                 return true;
             }
         }
-        false
+        false
+    }
+
+    /// Return the persons available on the day for the event, sorted by name to have a deterministic result.
+    fn persons_available<'a>(
+        availabilities: &'a AvailabilitiesPerPerson,
+        day: &Date,
+        event: Event,
+    ) -> Vec<&'a Name> {
+        availabilities
+            .iter()
+            .filter(|(_, her_availabilities)| {
+                her_availabilities
+                    .get(day)
+                    .is_some_and(|events| events.contains(&event))
+            })
+            .map(|(name, _)| name)
+            .sorted()
+            .collect()
+    }
+
+    /// Returns true if the event is of the Second level and the day is a friday, a saturday or a sunday,
+    /// when one person can be on-call for several consecutive events.
+    fn is_second_level_on_weekend(day: Date, event: Event) -> bool {
+        event.is_second_level()
+            && matches!(
+                day.weekday(),
+                time::Weekday::Friday | time::Weekday::Saturday | time::Weekday::Sunday
+            )
+    }
+
+    /// Returns true if the day is in the week-end (saturday or sunday)
+    fn is_weekend(day: Date) -> bool {
+        day.weekday() == time::Weekday::Saturday || day.weekday() == time::Weekday::Sunday
+    }
+
+    /// Number of days / events the person is still available for.
+    fn slot_count(availabilities: &AvailabilitiesPerPerson, name: &Name) -> usize {
+        availabilities.get(name).map_or(0, |a| a.slot_count())
+    }
+
+    /// Order the days by the number of days / events left to the scarcest person available on each of them.
+    fn scarcest_first(
+        availabilities: &AvailabilitiesPerPerson,
+        days_and_names: Vec<(Date, Vec<Name>)>,
+    ) -> Vec<(Date, Vec<Name>)> {
+        days_and_names
+            .into_iter()
+            .sorted_by_cached_key(|(_, names)| {
+                names
+                    .iter()
+                    .map(|name| Self::slot_count(availabilities, name))
+                    .min()
+            })
+            .collect()
+    }
+
+    /// Return the days with the least availabilities for the event passed in argument
+    fn get_days_with_least_availabilities(
+        availabilities: &AvailabilitiesPerPerson,
+        within_days: &[Date],
+        event: Event,
+    ) -> Vec<(Date, Vec<Name>)> {
+        let mut availabilities_per_day = HashMap::new();
+        let mut days_per_availabilities = HashMap::new();
+        // Sorting the days allow to have a deterministic result
+        for day in within_days.iter().sorted() {
+            let persons = Self::persons_available(availabilities, day, event);
+            let persons_len = persons.len();
+            availabilities_per_day.insert(day, persons);
+            days_per_availabilities
+                .entry(persons_len)
+                .and_modify(|d: &mut Vec<&Date>| d.push(day))
+                .or_insert(vec![day]);
+        }
+        let &least = days_per_availabilities.keys().min().expect("No day found");
+        let mut days_and_names = Vec::new();
+        for &day in days_per_availabilities.get(&least).unwrap() {
+            let names = availabilities_per_day.get(day).unwrap();
+            days_and_names.push((*day, names.iter().map(|name| name.to_string()).collect()));
+        }
+        days_and_names
+    }
+
+    /// Put in front the remaining day the first assignment strategy fills first, keeping the other days in order.
+    fn put_first_day_first(
+        &self,
+        availabilities: &AvailabilitiesPerPerson,
+        remaining_days: &[Date],
+        event: Event,
+        days_and_names: Vec<(Date, Vec<Name>)>,
+    ) -> Vec<(Date, Vec<Name>)> {
+        let first_day = match self.first_assignment_strategy {
+            FirstAssignmentStrategy::MostConstrained => return days_and_names,
+            FirstAssignmentStrategy::DeadlineFirst => Self::last_remaining_day(remaining_days),
+            FirstAssignmentStrategy::EarliestFirst => remaining_days.iter().min().copied(),
+            FirstAssignmentStrategy::Random(_) if self.seed.is_some() => {
+                remaining_days.choose(&mut *self.rng.borrow_mut()).copied()
+            }
+            FirstAssignmentStrategy::Random(seed) => {
+                let mut rng =
+                    SmallRng::seed_from_u64(seed.wrapping_add(remaining_days.len() as u64));
+                remaining_days.choose(&mut rng).copied()
+            }
+        };
+        let Some(first_day) = first_day else {
+            return days_and_names;
+        };
+        let names = Self::persons_available(availabilities, &first_day, event)
+            .into_iter()
+            .map(|name| name.to_string())
+            .collect();
+        std::iter::once((first_day, names))
+            .chain(
+                days_and_names
+                    .into_iter()
+                    .filter(|(day, _)| *day != first_day),
+            )
+            .collect()
+    }
+
+    /// The last remaining day of the month, filled first by the deadline first strategy.
+    fn last_remaining_day(remaining_days: &[Date]) -> Option<Date> {
+        remaining_days.iter().max().copied()
+    }
+
+    fn from_lines(lines: &mut std::str::Lines) -> Self {
+        let first_line = lines.next().expect("Empty file!");
+        let (from, to) = Self::parse_header(first_line).unwrap_or_else(|error| panic!("{}", error));
+        let calendar = Calendar::new(from, to);
+
+        let mut availabilities = AvailabilitiesPerPerson::new();
+        let mut warnings = Vec::new();
+        while let Some(line) = lines.next().as_mut() {
+            let (name, availabilities_str) = line.split_once([',', ';']).expect("Name missing");
+            let (from, to) = (calendar.from(), calendar.to());
+            let warning = match availabilities.get_mut(name) {
+                Some(her_availabilities) => her_availabilities.merge(from, to, availabilities_str),
+                None => {
+                    let (her_availabilities, warning) =
+                        Availabilities::from_str(from, to, availabilities_str);
+                    availabilities.insert(name.to_string(), her_availabilities);
+                    warning
+                }
+            };
+            if let Some(warning) = warning {
+                warnings.push(format!("{}: {}", name, warning));
+            }
+        }
+
+        let mut calendar_maker = Self::new(calendar, availabilities);
+        calendar_maker.warnings = warnings;
+        calendar_maker
+    }
+
+    /// Parse the first row of the CSV file, i.e. the month, the year and the days, and return the first and the last
+    /// days.
+    fn parse_header(first_line: &str) -> Result<(Date, Date), AubepineError> {
+        fn number<T: std::str::FromStr<Err = std::num::ParseIntError>>(
+            token: &str,
+        ) -> Result<T, AubepineError> {
+            token
+                .parse()
+                .map_err(|error| AubepineError::InvalidNumber(1, token.to_string(), error))
+        }
+        let tokens = first_line.split([',', ';']).collect::<Vec<&str>>();
+        let [month, year, first_day, ref other_days @ ..] = tokens[..] else {
+            return Err(AubepineError::InvalidCsv(format!(
+                "The header '{}' needs a month, a year and the days",
+                first_line
+            )));
+        };
+        let last_day = other_days.last().copied().unwrap_or(first_day);
+        let month = month_from_label(month)
+            .ok_or_else(|| AubepineError::InvalidCsv(format!("Unknown month '{}'", month)))?;
+        let year: i32 = number(year)?;
+        let (first_day, last_day): (u8, u8) = (number(first_day)?, number(last_day)?);
+        // The days wrap around when the range ends in the next month, possibly of the next year
+        let (to_year, to_month) = match month {
+            _ if last_day >= first_day => (year, month),
+            time::Month::December => (year + 1, time::Month::January),
+            month => (year, month.next()),
+        };
+        let invalid_day = |error: time::error::ComponentRange| {
+            AubepineError::InvalidCsv(format!("Invalid day in the header: {}", error))
+        };
+        let from = Date::from_calendar_date(year, month, first_day).map_err(invalid_day)?;
+        let to = Date::from_calendar_date(to_year, to_month, last_day).map_err(invalid_day)?;
+        Ok((from, to))
+    }
+
+    fn new(calendar: Calendar, availabilities: AvailabilitiesPerPerson) -> Self {
+        let persons = availabilities
+            .keys()
+            .map(|name| {
+                (
+                    name.clone(),
+                    Person::from_csv_name(name, SUBCONTRACTOR_PREFIX),
+                )
+            })
+            .collect();
+
+        Self {
+            calendar,
+            initial_availabilities: availabilities.clone(),
+            availabilities,
+            persons,
+            min_seniority_per_event: HashMap::new(),
+            qualified_events: RoleMatrix::new(),
+            on_call_chains: Vec::new(),
+            warnings: Vec::new(),
+            problematic_days: BTreeMap::new(),
+            subco_explanations: Vec::new(),
+            max_subcontractor: 0,
+            verbose: false,
+            calendar_made: false,
+            balancing_strategy: BalancingStrategy::default(),
+            imported_balance: HashMap::new(),
+            tie_breaking_strategy: TieBreakingStrategy::default(),
+            first_assignment_strategy: FirstAssignmentStrategy::default(),
+            scarce_persons_first: false,
+            employees_first: false,
+            event_groups: Vec::new(),
+            max_gap: None,
+            rest_hours_after_night: 0,
+            max_weekly_oncall_hours: None,
+            holidays: Vec::new(),
+            avoid_same_day_double: false,
+            senior_night_pairing: false,
+            seed: None,
+            rng: RefCell::new(SmallRng::from_entropy()),
+            on_progress: None,
+        }
+    }
+}
+
+/// First row of the CSV file: the month and year of the first day, then the number of each day.
+fn csv_header(calendar: &Calendar) -> String {
+    let from = calendar.from();
+    let mut header = format!("{},{}", month_label(from.month()), from.year());
+    for day in calendar.days() {
+        header.push_str(&format!(",{}", day.day()));
+    }
+    header.push_str("\r\n");
+    header
+}
+
+/// Month of the header of the CSV file, whatever the case.
+fn month_from_label(label: &str) -> Option<time::Month> {
+    match label.to_ascii_uppercase().as_str() {
+        "JANVIER" => Some(time::Month::January),
+        "FEVRIER" => Some(time::Month::February),
+        "MARS" => Some(time::Month::March),
+        "AVRIL" => Some(time::Month::April),
+        "MAI" => Some(time::Month::May),
+        "JUIN" => Some(time::Month::June),
+        "JUILLET" => Some(time::Month::July),
+        "AOUT" => Some(time::Month::August),
+        "SEPTEMBRE" => Some(time::Month::September),
+        "OCTOBRE" => Some(time::Month::October),
+        "NOVEMBRE" => Some(time::Month::November),
+        "DECEMBRE" => Some(time::Month::December),
+        _ => None,
+    }
+}
+
+/// Name of the month, as expected in the header of the CSV file.
+fn month_label(month: time::Month) -> &'static str {
+    match month {
+        time::Month::January => "JANVIER",
+        time::Month::February => "FEVRIER",
+        time::Month::March => "MARS",
+        time::Month::April => "AVRIL",
+        time::Month::May => "MAI",
+        time::Month::June => "JUIN",
+        time::Month::July => "JUILLET",
+        time::Month::August => "AOUT",
+        time::Month::September => "SEPTEMBRE",
+        time::Month::October => "OCTOBRE",
+        time::Month::November => "NOVEMBRE",
+        time::Month::December => "DECEMBRE",
+    }
+}
+
+impl Default for CalendarMaker {
+    /// Empty calendar maker, without any person, covering the current month.
+    fn default() -> Self {
+        let today = time::OffsetDateTime::now_utc().date();
+        let from = today.replace_day(1).unwrap();
+        let to = today
+            .replace_day(today.month().length(today.year()))
+            .unwrap();
+        Self::new(Calendar::new(from, to), HashMap::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Event::{FirstDaily, FirstNightly};
+
+    /// CSV content for the days of January 2025 passed in argument, the persons being available for all the events.
+    fn all_available(names: &[&str], days: &[u8]) -> String {
+        let mut content = format!("JANVIER,2025,{}\r\n", days.iter().join(","));
+        for name in names {
+            for event in Event::ALL {
+                let label = event.to_label(Locale::default());
+                content.push_str(&format!("{},{}{}\r\n", name, label, ",".repeat(days.len())));
+            }
+        }
+        content
+    }
+
+    #[test]
+    fn test_check_csv() {
+        let content = "JANVIER,2025,1,2\r\nAlice,1ère SF jour,,x\r\nAlice,SF 1,,\r\n";
+        assert_eq!(
+            CalendarMaker::check_csv(content),
+            Err(AubepineError::UnknownLevel("SF 1".to_string(), Some(3)))
+        );
+        let content = "JANVIER,2O25,1,2\r\nAlice,1ère SF jour,,x\r\n";
+        match CalendarMaker::check_csv(content) {
+            Err(error @ AubepineError::InvalidNumber(1, _, _)) => {
+                assert!(std::error::Error::source(&error).is_some());
+                assert!(error
+                    .to_string()
+                    .starts_with("Invalid number '2O25' at line 1"));
+            }
+            result => panic!("Expected an invalid number, got {:?}", result),
+        }
+        assert_eq!(CalendarMaker::check_csv("JANVIER,2025,1\r\n"), Ok(()));
+        assert!(matches!(
+            CalendarMaker::check_csv("JANVIER,2025,1,32\r\n"),
+            Err(AubepineError::InvalidCsv(_))
+        ));
+        assert_eq!(
+            CalendarMaker::check_csv("DECEMBRE,2024,30,31,1,2\r\nAlice,1ère SF jour,,,,\r\n"),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_from_lines() {
+        let content =
+            "JANVIER,2025,1,2,3,4,5\r\nAlice,1ère SF jour,,,,x,x\r\nAlice,1ère SF nuit,x,,,x,\r\n";
+        let calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        assert!(calendar_maker.calendar.from() == Date::from_ordinal_date(2025, 1).unwrap());
+        assert!(calendar_maker.calendar.get_all().len() == 5);
+        assert!(calendar_maker.availabilities.keys().any(|a| a == "Alice"));
+        assert_eq!(calendar_maker.persons(), vec!["Alice"]);
+        assert_eq!(calendar_maker.person_count(), 1);
+        assert!(
+            calendar_maker
+                .availabilities
+                .get("Alice")
+                .unwrap()
+                .get(&calendar_maker.calendar.from())
+                .unwrap()
+                == &vec![FirstDaily]
+        );
+        assert!(
+            calendar_maker
+                .availabilities
+                .get("Alice")
+                .unwrap()
+                .get(&Date::from_ordinal_date(2025, 5).unwrap())
+                .unwrap()
+                == &vec![FirstNightly]
+        );
+    }
+
+    #[test]
+    fn test_set_seed() {
+        use rand::Rng;
+        let content = "JANVIER,2025,1,2,3\r\nAlice,1ère SF jour,,x,\r\n";
+        let mut calendar_maker_1 = CalendarMaker::from_lines(&mut content.lines());
+        let mut calendar_maker_2 = CalendarMaker::from_lines(&mut content.lines());
+        assert_eq!(calendar_maker_1.seed(), None);
+        calendar_maker_1.set_seed(42);
+        calendar_maker_2.set_seed(42);
+        assert_eq!(calendar_maker_1.seed(), Some(42));
+        assert_eq!(
+            calendar_maker_1.rng.get_mut().gen::<u64>(),
+            calendar_maker_2.rng.get_mut().gen::<u64>()
+        );
+        // The strategy chosen is kept, whatever the order of the calls
+        calendar_maker_1.set_tie_breaking_strategy(TieBreakingStrategy::Alphabetical);
+        calendar_maker_1.set_seed(42);
+        assert_eq!(
+            calendar_maker_1.tie_breaking_strategy,
+            TieBreakingStrategy::Alphabetical
+        );
+        // The random strategies draw from the seeded random number generator
+        for calendar_maker in [&mut calendar_maker_1, &mut calendar_maker_2] {
+            calendar_maker.set_tie_breaking_strategy(TieBreakingStrategy::Random(0));
+            calendar_maker.set_first_assignment_strategy(FirstAssignmentStrategy::Random(0));
+            calendar_maker.set_seed(42);
+        }
+        calendar_maker_1.make_calendar(0, false);
+        calendar_maker_2.make_calendar(0, false);
+        assert_eq!(calendar_maker_1.calendar, calendar_maker_2.calendar);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_make_calendar_async() {
+        let content = "JANVIER,2025,1,2,3\r\nAlice,1ère SF jour,,x,\r\nBob,1ère SF jour,,,x,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let result = calendar_maker.make_calendar_async(0).await;
+        // Nobody is available for the other events, so no solution is found and the calendar is left empty
+        match result {
+            Err(ScheduleError::Unfilled(empty_slots)) => assert_eq!(empty_slots.len(), 3 * 4),
+            _ => panic!("Expected unfilled days"),
+        }
+    }
+
+    #[test]
+    fn test_set_on_progress() {
+        use std::sync::Mutex;
+
+        let content = "JANVIER,2025,1,2,3\r\nAlice,1ère SF jour,,x,\r\nBob,1ère SF jour,,,x,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let progresses = Arc::new(Mutex::new(Vec::new()));
+        let callback_progresses = Arc::clone(&progresses);
+        calendar_maker.set_on_progress(move |progress| {
+            callback_progresses.lock().unwrap().push(progress);
+        });
+        let _ = calendar_maker.solve_with_partial_result(0);
+        // Nobody is available for the other events, so all the permutations are tried
+        let progresses = progresses.lock().unwrap();
+        assert_eq!(progresses.len(), 24);
+        assert_eq!(
+            progresses.last(),
+            Some(&SearchProgress {
+                permutations_tried: 24,
+                permutation_count: 24,
+                subcontractors: 0
+            })
+        );
+    }
+
+    #[cfg(feature = "progress")]
+    #[test]
+    fn test_make_calendar_with_progress_bar() {
+        let content = "JANVIER,2025,1,2,3\r\nAlice,1ère SF jour,,x,\r\nBob,1ère SF jour,,,x,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.make_calendar_with_progress_bar(0, false);
+        assert!(calendar_maker.on_progress.is_none());
+    }
+
+    #[test]
+    fn test_block_from_ical() {
+        let content = "JANVIER,2025,1,2,3\r\nAlice,1ère SF jour,,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let ical = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250102\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        assert_eq!(
+            calendar_maker.block_from_ical("Bob", ical),
+            Err(AubepineError::UnknownPerson("Bob".to_string()))
+        );
+        calendar_maker.block_from_ical("Alice", ical).unwrap();
+        let alice = calendar_maker.availabilities.get("Alice").unwrap();
+        assert_eq!(
+            alice
+                .get(&Date::from_ordinal_date(2025, 1).unwrap())
+                .unwrap(),
+            &vec![FirstDaily]
+        );
+        assert_eq!(
+            alice
+                .get(&Date::from_ordinal_date(2025, 2).unwrap())
+                .unwrap(),
+            &vec![]
+        );
+        assert_eq!(
+            alice
+                .get(&Date::from_ordinal_date(2025, 3).unwrap())
+                .unwrap(),
+            &vec![FirstDaily]
+        );
+    }
+
+    #[test]
+    fn test_require_min_seniority_for_event() {
+        let content =
+            "JANVIER,2025,1,2\r\nAlice,1ère SF jour,,\r\nAlice,2ème SF jour,,\r\nBob,1ère SF jour,,\r\nBob,2ème SF jour,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker
+            .person_mut("Alice")
+            .unwrap()
+            .set_seniority(Seniority::Senior);
+        calendar_maker.require_min_seniority_for_event(Event::SecondDaily, Seniority::Senior);
+        let availabilities = calendar_maker.qualified_availabilities();
+        let day = Date::from_ordinal_date(2025, 1).unwrap();
+        assert_eq!(
+            availabilities.get("Alice").unwrap().get(&day).unwrap(),
+            &vec![FirstDaily, Event::SecondDaily]
+        );
+        // Bob is still junior, he can only be on-call as first level
+        assert_eq!(
+            availabilities.get("Bob").unwrap().get(&day).unwrap(),
+            &vec![FirstDaily]
+        );
+    }
+
+    #[test]
+    fn test_set_qualified_events() {
+        let content =
+            "JANVIER,2025,1,2\r\nAlice,1ère SF jour,,\r\nAlice,2ème SF jour,,\r\nBob,1ère SF jour,,\r\nBob,2ème SF jour,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.set_qualified_events("Bob", &[FirstDaily, FirstNightly]);
+        let availabilities = calendar_maker.qualified_availabilities();
+        let day = Date::from_ordinal_date(2025, 1).unwrap();
+        assert_eq!(
+            availabilities.get("Alice").unwrap().get(&day).unwrap(),
+            &vec![FirstDaily, Event::SecondDaily]
+        );
+        // Bob is available for the Second level, but not qualified
+        assert_eq!(
+            availabilities.get("Bob").unwrap().get(&day).unwrap(),
+            &vec![FirstDaily]
+        );
+    }
+
+    #[test]
+    fn test_solve_with_role_constraints() {
+        // Monday 6th and tuesday 7th, everybody is available for everything
+        let names = ["A", "B", "C", "D", "E", "F", "G", "H"];
+        let content = all_available(&names, &[6, 7]);
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        // Only E, F, G and H are certified for the Second level
+        let roles = names
+            .iter()
+            .map(|name| {
+                let events = if *name < "E" {
+                    vec![FirstDaily, FirstNightly]
+                } else {
+                    vec![Event::SecondDaily, Event::SecondNightly]
+                };
+                (name.to_string(), events)
+            })
+            .collect::<RoleMatrix>();
+        calendar_maker.solve_with_role_constraints(&roles, 0);
+        assert!(calendar_maker.empty_slots().is_empty());
+        for events in calendar_maker.calendar.get_all().values() {
+            for (event, name) in events {
+                assert!(roles[name].contains(event));
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_hours_per_week() {
+        let content = "JANVIER,2025,1,2,3\r\nAlice,1ère SF jour,,,\r\nBob,1ère SF jour,,,\r\nCharlie,1ère SF jour,,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let alice = |calendar: &Calendar| calendar.assignments_for_person("Alice").len();
+        let (_, new_calendar, _, _) = calendar_maker.find_next(
+            calendar_maker.availabilities.clone(),
+            calendar_maker.calendar.clone(),
+            HashMap::new(),
+            Event::FirstDaily,
+            0,
+        );
+        assert_eq!(alice(&new_calendar), 1);
+        // From Wednesday to Friday, it's the same week
+        calendar_maker
+            .person_mut("Alice")
+            .unwrap()
+            .set_max_hours_per_week(Event::FirstDaily.duration_hours() - 1);
+        let (_, new_calendar, _, _) = calendar_maker.find_next(
+            calendar_maker.availabilities.clone(),
+            calendar_maker.calendar.clone(),
+            HashMap::new(),
+            Event::FirstDaily,
+            0,
+        );
+        assert!(new_calendar.get_empty_days(&Event::FirstDaily).is_empty());
+        assert_eq!(alice(&new_calendar), 0);
+    }
+
+    #[test]
+    fn test_max_weekly_oncall_hours() {
+        // From monday 6th to sunday 12th, nobody can be on-call two days in a row, so 4 times at most
+        let content = "JANVIER,2025,6,7,8,9,10,11,12\r\nAlice,1ère SF jour,,,,,,,\r\nBob,1ère SF jour,,,,,,,\r\nCharlie,1ère SF jour,,,,,,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let hours = Event::FirstDaily.duration_hours() as u16;
+        let find_next = |calendar_maker: &CalendarMaker| {
+            calendar_maker
+                .find_next(
+                    calendar_maker.availabilities.clone(),
+                    calendar_maker.calendar.clone(),
+                    HashMap::new(),
+                    Event::FirstDaily,
+                    0,
+                )
+                .1
+        };
+        calendar_maker.max_weekly_oncall_hours = Some(3 * hours);
+        let new_calendar = find_next(&calendar_maker);
+        assert!(new_calendar.get_empty_days(&Event::FirstDaily).is_empty());
+        for name in ["Alice", "Bob", "Charlie"] {
+            assert!(new_calendar.assignments_for_person(name).len() <= 3);
+        }
+        // 3 persons on-call twice can't cover 7 days
+        calendar_maker.max_weekly_oncall_hours = Some(3 * hours - 1);
+        let new_calendar = find_next(&calendar_maker);
+        assert!(!new_calendar.get_empty_days(&Event::FirstDaily).is_empty());
+        // The lowest maximum applies
+        calendar_maker.max_weekly_oncall_hours = Some(4 * hours);
+        calendar_maker
+            .person_mut("Alice")
+            .unwrap()
+            .set_max_hours_per_week(hours as u8);
+        let new_calendar = find_next(&calendar_maker);
+        assert_eq!(new_calendar.assignments_for_person("Alice").len(), 1);
+    }
+
+    #[test]
+    fn test_solve_with_max_weekly_oncall_hours() {
+        // From monday 6th to sunday 12th, 8 persons for 28 days / events
+        let content = all_available(
+            &["A", "B", "C", "D", "E", "F", "G", "H"],
+            &[6, 7, 8, 9, 10, 11, 12],
+        );
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.solve_with_max_weekly_oncall_hours(48, 0);
+        assert!(calendar_maker.empty_slots().is_empty());
+        let day_6 = Date::from_ordinal_date(2025, 6).unwrap();
+        for name in ["A", "B", "C", "D", "E", "F", "G", "H"] {
+            assert!(
+                calendar_maker
+                    .calendar
+                    .hours_in_week_for_person(name, day_6)
+                    <= 48
+            );
+        }
+        assert_eq!(calendar_maker.max_weekly_oncall_hours, None);
+    }
+
+    #[test]
+    fn test_solve_with_swap_table() {
+        // From monday 6th to thursday 9th, Alice is on-call for the 1st daily on the 6th and Bob on the 9th
+        let mut content = all_available(&["D", "E", "F", "G", "H", "I", "J", "K"], &[6, 7, 8, 9]);
+        content.push_str("Alice,1ère SF jour,,,,\r\nBob,1ère SF jour,,,,\r\n");
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let day = |ordinal| Date::from_ordinal_date(2025, ordinal).unwrap();
+        calendar_maker
+            .calendar
+            .set_for(day(6), FirstDaily, "Alice".to_string());
+        calendar_maker
+            .calendar
+            .set_for(day(9), FirstDaily, "Bob".to_string());
+        calendar_maker.availabilities = calendar_maker.availabilities_for(&calendar_maker.calendar);
+        let (alice, bob) = ("Alice".to_string(), "Bob".to_string());
+
+        // Alice isn't on-call on the 9th, the first swap is undone
+        let swaps = [
+            (day(6), FirstDaily, alice.clone(), bob.clone()),
+            (day(9), FirstDaily, alice.clone(), bob.clone()),
+        ];
+        assert_eq!(
+            calendar_maker.solve_with_swap_table(&swaps, 0),
+            Err(AubepineError::NotOnCall(alice.clone(), day(9), FirstDaily))
+        );
+        assert_eq!(
+            calendar_maker.calendar.get_for(&day(6), &FirstDaily),
+            Some(&alice)
+        );
+        assert_eq!(calendar_maker.empty_slots().len(), 4 * 4 - 2);
+
+        let swaps = [
+            (day(6), FirstDaily, alice.clone(), bob.clone()),
+            (day(9), FirstDaily, bob.clone(), alice.clone()),
+        ];
+        calendar_maker.solve_with_swap_table(&swaps, 0).unwrap();
+        assert_eq!(
+            calendar_maker.calendar.get_for(&day(6), &FirstDaily),
+            Some(&bob)
+        );
+        assert_eq!(
+            calendar_maker.calendar.get_for(&day(9), &FirstDaily),
+            Some(&alice)
+        );
+        assert!(calendar_maker.empty_slots().is_empty());
+        assert!(calendar_maker
+            .check_consecutive_day_rule_violations()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_is_within_contract() {
+        // Friday 31st of january to sunday 2nd of february
+        let content = "JANVIER,2025,31,1,2\r\nAlice,2ème SF jour,,,\r\nAlice,2ème SF nuit,,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let alice = "Alice".to_string();
+        let day_31 = Date::from_ordinal_date(2025, 31).unwrap();
+        let day_32 = Date::from_ordinal_date(2025, 32).unwrap();
+        let day_33 = Date::from_ordinal_date(2025, 33).unwrap();
+        let mut calendar = calendar_maker.calendar.clone();
+        calendar.set_for(day_31, Event::SecondDaily, alice.clone());
+        calendar.set_for(day_32, Event::SecondDaily, alice.clone());
+        assert!(calendar_maker.is_within_contract(&calendar, &alice, day_32, Event::SecondNightly));
+
+        // 12 hours in january, 12 more in february
+        let person = calendar_maker.person_mut("Alice").unwrap();
+        person.set_max_hours_per_month(12);
+        assert!(!calendar_maker.is_within_contract(
+            &calendar,
+            &alice,
+            day_32,
+            Event::SecondNightly
+        ));
+        calendar.unset_for(&day_32, &Event::SecondDaily);
+        assert!(calendar_maker.is_within_contract(&calendar, &alice, day_32, Event::SecondNightly));
+        assert!(!calendar_maker.is_within_contract(
+            &calendar,
+            &alice,
+            day_31,
+            Event::SecondNightly
+        ));
+
+        // 12 hours between the 2nd daily of the 31st and the one of the 1st, none before the 2nd nightly
+        let person = calendar_maker.person_mut("Alice").unwrap();
+        person.set_max_hours_per_month(u16::MAX);
+        person.set_min_rest_hours(12);
+        let mut calendar = calendar_maker.calendar.clone();
+        calendar.set_for(day_32, Event::SecondDaily, alice.clone());
+        assert!(calendar_maker.is_within_contract(&calendar, &alice, day_31, Event::SecondDaily));
+        assert!(calendar_maker.is_within_contract(&calendar, &alice, day_33, Event::SecondDaily));
+        assert!(!calendar_maker.is_within_contract(
+            &calendar,
+            &alice,
+            day_32,
+            Event::SecondNightly
+        ));
+        assert!(!calendar_maker.is_within_contract(
+            &calendar,
+            &alice,
+            day_31,
+            Event::SecondNightly
+        ));
+        calendar_maker
+            .person_mut("Alice")
+            .unwrap()
+            .set_min_rest_hours(13);
+        assert!(!calendar_maker.is_within_contract(&calendar, &alice, day_31, Event::SecondDaily));
+    }
+
+    #[test]
+    fn test_solve_respecting_contracts() {
+        // From monday 6th to sunday 12th, everybody is available for everything
+        let names = ["A", "B", "C", "D", "E", "F", "G", "H", "I", "J"];
+        let content = all_available(&names, &[6, 7, 8, 9, 10, 11, 12]);
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        // A works only at night, twice at most
+        let contract = ContractType {
+            allowed_events: vec![FirstNightly, Event::SecondNightly],
+            max_monthly_hours: 24,
+            max_weekly_hours: 48,
+            min_rest_between_hours: 48,
+        };
+        // Z is unknown, her contract is ignored
+        let contracts = HashMap::from([
+            ("A".to_string(), contract.clone()),
+            ("Z".to_string(), contract),
+        ]);
+        calendar_maker.solve_respecting_contracts(contracts, 0);
+        assert!(calendar_maker.empty_slots().is_empty());
+        let assignments = calendar_maker.calendar.assignments_for_person("A");
+        assert!(assignments.len() <= 2);
+        assert!(assignments.iter().all(|(_, event)| event.is_nightly()));
+        if let [(first, _), (second, _)] = assignments[..] {
+            assert!(first.days_until(second) >= 3);
+        }
+        assert_eq!(calendar_maker.persons["A"].max_hours_per_week(), Some(48));
+        assert_eq!(calendar_maker.persons["A"].min_rest_hours(), Some(48));
+    }
+
+    #[test]
+    fn test_validate_on_call_chain() {
+        let content = "JANVIER,2025,1,2\r\nAlice,1ère SF jour,,\r\nBob,1ère SF jour,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let day_1 = Date::from_ordinal_date(2025, 1).unwrap();
+        let day_2 = Date::from_ordinal_date(2025, 2).unwrap();
+        calendar_maker
+            .calendar
+            .set_for(day_1, FirstDaily, "Alice".to_string());
+        calendar_maker
+            .calendar
+            .set_for(day_1, Event::SecondNightly, "Bob".to_string());
+        calendar_maker
+            .calendar
+            .set_for(day_2, FirstDaily, "Bob".to_string());
+        calendar_maker.add_on_call_chain("Alice", "Bob", FirstDaily);
+        calendar_maker.add_on_call_chain("Bob", "Bob", FirstDaily);
+        assert_eq!(
+            calendar_maker.validate_on_call_chain(),
+            vec![
+                (
+                    day_1,
+                    FirstDaily,
+                    "Bob, backup of Alice, is also on-call for SecondNightly".to_string()
+                ),
+                (day_2, FirstDaily, "Bob is her own backup".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reassign() {
+        let content = "JANVIER,2025,1,2,3\r\nAlice,1ère SF jour,,,\r\nBob,1ère SF jour,,,x\r\nCharlie,1ère SF jour,,x,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let day = |ordinal| Date::from_ordinal_date(2025, ordinal).unwrap();
+        calendar_maker
+            .calendar
+            .set_for(day(1), FirstDaily, "Alice".to_string());
+        calendar_maker.availabilities = calendar_maker.availabilities_for(&calendar_maker.calendar);
+        assert_eq!(
+            calendar_maker.reassign(day(1), FirstDaily, "Dave", false),
+            Err(AubepineError::UnknownPerson("Dave".to_string()))
+        );
+        assert_eq!(
+            calendar_maker.reassign(day(4), FirstDaily, "Bob", false),
+            Err(AubepineError::DateOutOfRange(day(4)))
+        );
+        assert_eq!(
+            calendar_maker.reassign(day(3), FirstDaily, "Bob", false),
+            Err(AubepineError::NotAvailable(
+                "Bob".to_string(),
+                day(3),
+                FirstDaily
+            ))
+        );
+        calendar_maker
+            .reassign(day(1), FirstDaily, "Bob", false)
+            .unwrap();
+        assert_eq!(
+            calendar_maker.calendar.get_for(&day(1), &FirstDaily),
+            Some(&"Bob".to_string())
+        );
+        // Alice is available again the 2nd day, Bob is not anymore
+        let is_available = |calendar_maker: &CalendarMaker, name: &str, ordinal| {
+            calendar_maker
+                .availabilities
+                .get(name)
+                .unwrap()
+                .get(&day(ordinal))
+                .unwrap()
+                == &vec![FirstDaily]
+        };
+        assert!(is_available(&calendar_maker, "Alice", 2));
+        assert!(!is_available(&calendar_maker, "Bob", 2));
+        // Forcing ignores the availabilities
+        calendar_maker
+            .reassign(day(2), FirstDaily, "Charlie", true)
+            .unwrap();
+        assert_eq!(
+            calendar_maker.calendar.get_for(&day(2), &FirstDaily),
+            Some(&"Charlie".to_string())
+        );
+    }
+
+    #[test]
+    fn test_from_json_config() {
+        let json = r#"{
+            "from": "2025-01-06",
+            "to": "2025-01-07",
+            "persons": [
+                { "name": "Alice", "availability": { "2025-01-06": ["FirstDaily"] } },
+                { "name": "Bob", "membership": "Employee", "availability": { "2025-01-07": ["FirstDaily"] } }
+            ]
+        }"#;
+        let mut calendar_maker = CalendarMaker::from_json_config(json).unwrap();
+        assert_eq!(calendar_maker.persons(), vec!["Alice", "Bob"]);
+        let day_6 = Date::from_ordinal_date(2025, 6).unwrap();
+        assert_eq!(
+            calendar_maker.available_persons_for(day_6, FirstDaily),
+            vec!["Alice"]
+        );
+        assert!(calendar_maker
+            .available_persons_for(day_6, FirstNightly)
+            .is_empty());
+        assert!(!calendar_maker.person_mut("Bob").unwrap().is_subcontractor());
+        assert!(CalendarMaker::from_json_config("[]").is_err());
+    }
+
+    #[test]
+    fn test_from_lines_with_subcontractor() {
+        let content = "JANVIER,2025,1,2\r\nAlice,1ère SF jour,,\r\nEXT-0,1ère SF jour,x,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        assert!(calendar_maker
+            .person_mut("EXT-0")
+            .unwrap()
+            .is_subcontractor());
+        assert!(!calendar_maker
+            .person_mut("Alice")
+            .unwrap()
+            .is_subcontractor());
+    }
+
+    #[test]
+    fn test_from_lines_with_repeated_name() {
+        // Alice's lines are not consecutive, and her 1st daily is given twice
+        let content = "JANVIER,2025,1,2,3\r\n\
+            Alice,1ère SF jour,,x,x\r\n\
+            Bob,1ère SF jour,,,\r\n\
+            Alice,1ère SF nuit,x,,x\r\n\
+            Alice,1ère SF jour,x,x,\r\n";
+        let calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        assert_eq!(calendar_maker.persons(), vec!["Alice", "Bob"]);
+        let alice = calendar_maker.availabilities.get("Alice").unwrap();
+        let day = |d| Date::from_ordinal_date(2025, d).unwrap();
+        assert_eq!(alice.get(&day(1)), Some(&vec![FirstDaily]));
+        assert_eq!(alice.get(&day(2)), Some(&vec![FirstNightly]));
+        assert_eq!(alice.get(&day(3)), Some(&vec![FirstDaily]));
+    }
+
+    #[test]
+    fn test_make_calendar_for_week() {
+        // From monday 6th (week 2) to monday 13th (week 3)
+        // A is on-call for the 1st daily on the 13th
+        let content = all_available(
+            &["A", "B", "C", "D", "E", "F", "G", "H"],
+            &[6, 7, 8, 9, 10, 11, 12, 13],
+        )
+        .replace("A,1ère SF jour,,,,,,,,\r\n", "A,1ère SF jour,,,,,,,,1\r\n");
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.take_initial_allocations(content.lines());
+
+        let week = calendar_maker.make_calendar_for_week(2).unwrap();
+        let day = |d| Date::from_calendar_date(2025, time::Month::January, d).unwrap();
+        assert_eq!((week.from(), week.to()), (day(6), day(12)));
+        assert!(week.get_empty_days(&FirstDaily).is_empty());
+        // A is on-call on the 13th, so she can't be on-call the day before
+        assert!(!CalendarMaker::is_on_call(
+            calendar_maker.calendar.events_on_day(&day(12)).unwrap(),
+            &"A".to_string()
+        ));
+        // The other week is untouched
+        assert_eq!(calendar_maker.empty_slots().len(), 3);
+        assert_eq!(
+            calendar_maker.calendar.get_for(&day(13), &FirstDaily),
+            Some(&"A".to_string())
+        );
+
+        assert_eq!(
+            calendar_maker.make_calendar_for_week(5).err(),
+            Some(ScheduleError::OutOfRange)
+        );
+
+        // From thursday 9th (week 2) to wednesday 15th (week 3), nobody is available for the 1st daily on the 9th,
+        // nor, once the first calendar is made, on the 13th: the subcontractor of week 3 may reuse the name of the
+        // one of week 2
+        let content = all_available(
+            &["A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L"],
+            &[9, 10, 11, 12, 13, 14, 15],
+        )
+        .replace("1ère SF jour,,,,,,,\r\n", "1ère SF jour,x,,,,,,\r\n");
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.make_calendar(1, false);
+        assert!(calendar_maker.empty_slots().is_empty());
+        for d in 13..=15 {
+            for event in Event::ALL {
+                calendar_maker.calendar.unset_for(&day(d), &event);
+            }
+        }
+        for her_availabilities in calendar_maker.initial_availabilities.values_mut() {
+            her_availabilities.pop_event(&day(13), FirstDaily);
+        }
+        calendar_maker.make_calendar_for_week(3).unwrap();
+        let subcontractor = calendar_maker
+            .calendar
+            .get_for(&day(13), &FirstDaily)
+            .unwrap()
+            .clone();
+        assert!(subcontractor.starts_with(SUBCONTRACTOR_PREFIX));
+        for (d, events) in calendar_maker.calendar.get_all() {
+            for (event, name) in events {
+                assert!(calendar_maker.persons.contains_key(name));
+                assert!(calendar_maker.initial_availabilities[name]
+                    .get(d)
+                    .is_some_and(|events| events.contains(event)));
+            }
+        }
+
+        // Over more than a year, only the first week 1 is filled
+        let from = Date::from_calendar_date(2024, time::Month::January, 1).unwrap();
+        let to = Date::from_calendar_date(2025, time::Month::January, 5).unwrap();
+        let mut calendar_maker = CalendarMaker::new(Calendar::new(from, to), HashMap::new());
+        match calendar_maker.make_calendar_for_week(1) {
+            Err(ScheduleError::Unfilled(empty_slots)) => {
+                assert_eq!(empty_slots.len(), 7 * 4);
+                assert!(empty_slots.iter().all(|(day, _)| day.year() == 2024));
+            }
+            result => panic!("Expected unfilled days, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_format_statistics() {
+        let content = "JANVIER,2025,1,2,3\r\nAlice,1ère SF jour,1,,1\r\nBob,1ère SF nuit,1,1,\r\nCharlie,1ère SF jour,,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.take_initial_allocations(content.lines());
+        assert_eq!(
+            calendar_maker.format_statistics(),
+            "Name    |  J  |  N  |  j  |  n  | Total | Deviation\r\n\
+            ---------------------------------------------------\r\n\
+            Alice   |   2 |   0 |   0 |   0 |     2 |      +0.7\r\n\
+            Bob     |   0 |   2 |   0 |   0 |     2 |      +0.7\r\n\
+            Charlie |   0 |   0 |   0 |   0 |     0 |      -1.3\r\n"
+        );
+    }
+
+    #[test]
+    fn test_describe_solution_quality() {
+        let content = "JANVIER,2025,1,2,3\r\nAlice,1ère SF jour,1,,1\r\nBob,1ère SF nuit,1,1,\r\nCharlie,1ère SF jour,,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.take_initial_allocations(content.lines());
+        assert_eq!(
+            calendar_maker.describe_solution_quality(),
+            "The schedule covers 4 of the 12 days / events across 3 days, 8 left empty. \
+            No subcontractor was required. \
+            The fairness score is 0.94 (good). \
+            The most overloaded person is Alice with 2 assignments (mean: 1.3, std dev: 0.9). \
+            The least loaded is Charlie with 0 assignments."
+        );
+
+        // Nobody is available for the 1st daily on monday 6th
+        let content = all_available(&["A", "B", "C", "D", "E", "F", "G", "H"], &[6, 7])
+            .replace("1ère SF jour,,", "1ère SF jour,x,");
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.make_calendar(1, false);
+        let description = calendar_maker.describe_solution_quality();
+        assert!(description.starts_with(
+            "The schedule covers all 8 days / events across 2 days. \
+            1 subcontractor was required (EXT-0 on 2025-01-06 / FirstDaily)."
+        ));
+        assert!(description.ends_with("with 0 assignments."));
+    }
+
+    #[test]
+    fn test_availability_heatmap() {
+        let content = "JANVIER,2025,1,2,3\r\n\
+            Bob,1ère SF jour,,x,\r\n\
+            Bob,2ème SF nuit,,,x\r\n\
+            Alice,1ère SF jour,x,x,x\r\n";
+        let calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        assert_eq!(
+            calendar_maker.availability_heatmap(),
+            "      | 01 02 03\r\n\
+            ----------------\r\n\
+            Alice |  ·  ·  ·\r\n\
+            Bob   |  2  1  1\r\n"
+        );
+    }
+
+    #[test]
+    fn test_clone_without_assignments() {
+        let content = "JANVIER,2025,1,2,3\r\nAlice,1ère SF jour,1,,\r\nBob,1ère SF jour,,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.take_initial_allocations(content.lines());
+        calendar_maker.set_seed(42);
+
+        let fresh = calendar_maker.clone_without_assignments();
+        assert_eq!(fresh.empty_slots().len(), 3 * 4);
+        assert_eq!(fresh.calendar_range(), calendar_maker.calendar_range());
+        assert_eq!(fresh.persons(), vec!["Alice", "Bob"]);
+        assert_eq!(fresh.seed(), Some(42));
+        // Alice is available again the day after her initial allocation
+        let day_2 = Date::from_ordinal_date(2025, 2).unwrap();
+        assert_eq!(
+            fresh.availabilities["Alice"].get(&day_2),
+            Some(&vec![FirstDaily])
+        );
+        assert_eq!(
+            calendar_maker.availabilities["Alice"].get(&day_2),
+            Some(&vec![])
+        );
+    }
+
+    #[test]
+    fn test_check_consecutive_day_rule_violations() {
+        // From thursday 2nd to sunday 5th
+        let content = "JANVIER,2025,2,3,4,5\r\n\
+            Alice,2ème SF jour,,1,1,\r\n\
+            Alice,2ème SF nuit,,,1,\r\n\
+            Bob,1ère SF jour,1,,,\r\n\
+            Bob,2ème SF nuit,,1,,\r\n\
+            Charlie,1ère SF nuit,,,,1\r\n\
+            Charlie,2ème SF jour,,,,1\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.take_initial_allocations(content.lines());
+        let day = |d| Date::from_calendar_date(2025, time::Month::January, d).unwrap();
+        // Alice is only on-call as Second level during the week-end, so she doesn't break the rule
+        assert_eq!(
+            calendar_maker.check_consecutive_day_rule_violations(),
+            vec![
+                (day(3), Event::SecondNightly, "Bob".to_string()),
+                (day(5), Event::SecondDaily, "Charlie".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Alice is on-call for [FirstDaily, SecondNightly] on 2025-01-02")]
+    fn test_assert_no_double_booking() {
+        // Thursday 2nd and Friday 3rd
+        let content = "JANVIER,2025,2,3\r\n\
+            Bob,2ème SF jour,,1\r\n\
+            Bob,2ème SF nuit,,1\r\n\
+            Alice,1ère SF jour,1,\r\n\
+            Alice,2ème SF nuit,1,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.take_initial_allocations(content.lines());
+        calendar_maker.assert_no_double_booking();
+    }
+
+    #[test]
+    fn test_check_event_distribution() {
+        let mut content = "JANVIER,2025,1,2,3,4,5\r\n".to_string();
+        for name in ["A", "B", "C", "D", "E", "F"] {
+            content.push_str(&format!("{},1ère SF jour,,,,,\r\n", name));
+        }
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        assert!(calendar_maker.check_event_distribution().is_empty());
+
+        for day in 1..=5 {
+            let day = Date::from_ordinal_date(2025, day).unwrap();
+            calendar_maker
+                .calendar
+                .set_for(day, FirstDaily, "A".to_string());
+        }
+        let flagged = calendar_maker.check_event_distribution();
+        assert_eq!(flagged.len(), 1);
+        assert_eq!((flagged[0].0.as_str(), flagged[0].1), ("A", 5));
+        assert!((flagged[0].2 - 5.0_f64.sqrt()).abs() < 1e-9);
+        assert!(calendar_maker
+            .format_statistics()
+            .ends_with("Warning: A is on-call 5 times, far above the others (z-score 2.2)\r\n"));
+    }
+
+    #[test]
+    fn test_generate_example_csv() {
+        let from = Date::from_calendar_date(2024, time::Month::December, 30).unwrap();
+        let to = Date::from_calendar_date(2025, time::Month::January, 2).unwrap();
+        let csv = CalendarMaker::generate_example_csv(
+            from,
+            to,
+            &[
+                ("Alice", &[FirstDaily, FirstNightly]),
+                ("Bob", &[Event::SecondDaily]),
+            ],
+        );
+        assert_eq!(
+            csv,
+            "DECEMBRE,2024,30,31,1,2\r\n\
+            Alice,1ère SF jour,,,,\r\n\
+            Alice,1ère SF nuit,,,,\r\n\
+            Bob,2ème SF jour,,,,\r\n"
+        );
+        // It can be read back, everyone being available
+        let calendar_maker = CalendarMaker::from_csv_string(&csv);
+        assert_eq!(calendar_maker.calendar_range(), (from, to));
+        assert_eq!(
+            calendar_maker.available_persons_for(to, FirstNightly),
+            vec!["Alice"]
+        );
+    }
+
+    #[test]
+    fn test_export_availability_csv() {
+        let content = "JANVIER,2025,1,2,3\r\n\
+            Bob,1ère SF jour,,,\r\n\
+            Alice,1ère SF jour,,1,\r\n\
+            Alice,1ère SF nuit,OFF,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.take_initial_allocations(content.lines());
+
+        // Alice is on-call on the 2nd, so she is no more available the day before and the day after
+        let expected = "JANVIER,2025,1,2,3\r\n\
+            Alice,1ère SF jour,x,x,x\r\n\
+            Alice,1ère SF nuit,x,x,x\r\n\
+            Alice,2ème SF jour,x,x,x\r\n\
+            Alice,2ème SF nuit,x,x,x\r\n\
+            Bob,1ère SF jour,,,\r\n\
+            Bob,1ère SF nuit,x,x,x\r\n\
+            Bob,2ème SF jour,x,x,x\r\n\
+            Bob,2ème SF nuit,x,x,x\r\n";
+        let exported = calendar_maker.export_availability_csv();
+        assert_eq!(exported, expected);
+
+        // The export can be imported back
+        let reimported = CalendarMaker::from_lines(&mut exported.lines());
+        assert_eq!(reimported.export_availability_csv(), expected);
+    }
+
+    #[test]
+    fn test_solve_for_month() {
+        let content = "FEVRIER,2024,1,2\r\nAlice,1ère SF jour,1,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.take_initial_allocations(content.lines());
+        assert_eq!(
+            calendar_maker.solve_for_month(2024, time::Month::February, 0),
+            Ok(())
+        );
+        let first_day = Date::from_calendar_date(2024, time::Month::February, 1).unwrap();
+        let leap_day = Date::from_calendar_date(2024, time::Month::February, 29).unwrap();
+        assert_eq!(calendar_maker.calendar.from(), first_day);
+        assert_eq!(calendar_maker.calendar.to(), leap_day);
+        assert_eq!(calendar_maker.calendar.get_all().len(), 29);
+        assert_eq!(
+            calendar_maker.calendar.get_for(&first_day, &FirstDaily),
+            Some(&"Alice".to_string())
+        );
+
+        assert_eq!(
+            calendar_maker.solve_for_month(1_000_000, time::Month::February, 0),
+            Err(AubepineError::InvalidYear(1_000_000))
+        );
+
+        // Solving another month first doesn't remove the availabilities of this one
+        let content = all_available(&["A", "B", "C", "D", "E", "F", "G", "H"], &[6, 7, 8]);
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker
+            .solve_for_month(2025, time::Month::February, 0)
+            .unwrap();
+        assert!(calendar_maker
+            .calendar
+            .get_all()
+            .keys()
+            .all(|day| day.month() == time::Month::February));
+        calendar_maker
+            .solve_for_month(2025, time::Month::January, 0)
+            .unwrap();
+        let day_6 = Date::from_ordinal_date(2025, 6).unwrap();
+        for availabilities in [
+            &calendar_maker.initial_availabilities,
+            &calendar_maker.availabilities,
+        ] {
+            assert_eq!(availabilities["A"].get(&day_6), Some(&Event::ALL.to_vec()));
+        }
+        assert_eq!(calendar_maker.calendar.get_all().len(), 31);
+    }
+
+    #[test]
+    fn test_default() {
+        let calendar_maker = CalendarMaker::default();
+        let today = time::OffsetDateTime::now_utc().date();
+        assert_eq!(calendar_maker.calendar.from().day(), 1);
+        assert_eq!(calendar_maker.calendar.from().month(), today.month());
+        assert_eq!(calendar_maker.calendar.to().month(), today.month());
+        assert_eq!(calendar_maker.calendar.to().next_day().unwrap().day(), 1);
+        assert_eq!(calendar_maker.person_count(), 0);
+    }
+
+    #[test]
+    fn test_set_calendar_range() {
+        let content = "JANVIER,2025,1,2,3\r\nAlice,1ère SF jour,,1,\r\nBob,1ère SF jour,1,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.take_initial_allocations(content.lines());
+        let day = |ordinal| Date::from_ordinal_date(2025, ordinal).unwrap();
+        assert_eq!(
+            calendar_maker.set_calendar_range(day(3), day(2)),
+            Err(AubepineError::InvalidRange(day(3), day(2)))
+        );
+        assert_eq!(calendar_maker.calendar_range(), (day(1), day(3)));
+        calendar_maker.set_calendar_range(day(2), day(4)).unwrap();
+        assert_eq!(calendar_maker.calendar_range(), (day(2), day(4)));
+        assert_eq!(calendar_maker.calendar.get_all().len(), 3);
+        assert_eq!(
+            calendar_maker.calendar.get_for(&day(2), &FirstDaily),
+            Some(&"Alice".to_string())
+        );
+        let bob = calendar_maker.availabilities.get("Bob").unwrap();
+        // Bob isn't on-call the 1st day anymore, so he's available again the 2nd day
+        assert_eq!(bob.get(&day(1)), None);
+        assert_eq!(bob.get(&day(2)).unwrap(), &vec![FirstDaily]);
+        assert_eq!(bob.get(&day(4)), None);
+    }
+
+    #[test]
+    fn test_merge_availabilities_for_person() {
+        let content = "JANVIER,2025,1,2\r\nAlice,1ère SF jour,,x\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let day_1 = Date::from_ordinal_date(2025, 1).unwrap();
+        let day_2 = Date::from_ordinal_date(2025, 2).unwrap();
+        assert_eq!(
+            calendar_maker.merge_availabilities_for_person("Alice", day_1, "SF 1,,"),
+            Err(AubepineError::UnknownLevel("SF 1".to_string(), None))
+        );
+        calendar_maker
+            .merge_availabilities_for_person("Alice", day_1, "1ère SF nuit,x,")
+            .unwrap();
+        calendar_maker
+            .merge_availabilities_for_person("Bob", day_2, "2ème SF jour,")
+            .unwrap();
+        let alice = calendar_maker.availabilities.get("Alice").unwrap();
+        assert_eq!(alice.get(&day_1).unwrap(), &vec![FirstDaily]);
+        assert_eq!(alice.get(&day_2).unwrap(), &vec![FirstNightly]);
+        let bob = calendar_maker.availabilities.get("Bob").unwrap();
+        assert_eq!(bob.get(&day_1), None);
+        assert_eq!(bob.get(&day_2).unwrap(), &vec![Event::SecondDaily]);
+        assert!(calendar_maker.person_mut("Bob").is_some());
+    }
+
+    #[test]
+    fn test_force_subcontractor() {
+        // Nobody is available for the 1st daily on monday 6th
+        let content = all_available(&["A", "B", "C", "D", "E", "F", "G", "H"], &[6, 7])
+            .replace("1ère SF jour,,", "1ère SF jour,x,");
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let day_6 = Date::from_ordinal_date(2025, 6).unwrap();
+        let day_8 = Date::from_ordinal_date(2025, 8).unwrap();
+        assert_eq!(
+            calendar_maker.force_subcontractor("CONTR-External", day_8, FirstDaily),
+            Err(AubepineError::DateOutOfRange(day_8))
+        );
+        calendar_maker
+            .force_subcontractor("CONTR-External", day_6, FirstDaily)
+            .unwrap();
+        assert!(calendar_maker.persons["CONTR-External"].is_subcontractor());
+        assert_eq!(
+            calendar_maker.available_persons_for(day_6, FirstDaily),
+            vec!["CONTR-External"]
+        );
+        assert!(calendar_maker
+            .available_persons_for(day_6, FirstNightly)
+            .iter()
+            .all(|name| name.as_str() != "CONTR-External"));
+
+        calendar_maker.make_calendar(0, false);
+        assert_eq!(
+            calendar_maker.calendar.get_for(&day_6, &FirstDaily),
+            Some(&"CONTR-External".to_string())
+        );
+        assert!(calendar_maker.empty_slots().is_empty());
+        assert!(calendar_maker
+            .check_consecutive_day_rule_violations()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_remove_subcontractors() {
+        // Nobody is available for the 1st daily on monday 6th
+        let names = ["A", "B", "C", "D", "E", "F", "G", "H"];
+        let content = all_available(&names, &[6, 7]).replace("1ère SF jour,,", "1ère SF jour,x,");
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.make_calendar(1, false);
+        let day_6 = Date::from_ordinal_date(2025, 6).unwrap();
+        assert_eq!(
+            calendar_maker.calendar.get_for(&day_6, &FirstDaily),
+            Some(&"EXT-0".to_string())
+        );
+
+        calendar_maker.remove_subcontractors();
+        assert_eq!(calendar_maker.empty_slots(), vec![(day_6, FirstDaily)]);
+        assert!(!calendar_maker.persons.contains_key("EXT-0"));
+        assert!(!calendar_maker.availabilities.contains_key("EXT-0"));
+        assert_eq!(
+            calendar_maker.explain_subcontractor_need(0),
+            "EXT-0 was not added"
+        );
+
+        // Everybody is now available for the 1st daily on the 6th
+        for name in names {
+            calendar_maker
+                .merge_availabilities_for_person(name, day_6, "1ère SF jour,")
+                .unwrap();
+        }
+        calendar_maker.make_calendar(0, false);
+        assert!(calendar_maker.empty_slots().is_empty());
+        assert_eq!(calendar_maker.person_count(), names.len());
+    }
+
+    #[test]
+    fn test_check_max_gap() {
+        // From monday 6th to sunday 12th, B isn't available on the 9th
+        let content = "JANVIER,2025,6,7,8,9,10,11,12\r\n\
+            A,1ère SF jour,,,,,,,\r\n\
+            B,1ère SF jour,,,,x,,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let day = |day| Date::from_ordinal_date(2025, day).unwrap();
+        calendar_maker
+            .calendar
+            .set_for(day(6), FirstDaily, "A".to_string());
+        calendar_maker
+            .calendar
+            .set_for(day(10), FirstDaily, "A".to_string());
+        assert_eq!(calendar_maker.check_max_gap(3), vec![]);
+        assert_eq!(
+            calendar_maker.check_max_gap(2),
+            vec![
+                ("A".to_string(), day(7), day(9)),
+                ("B".to_string(), day(6), day(8)),
+                ("B".to_string(), day(10), day(12)),
+            ]
+        );
+        assert_eq!(calendar_maker.check_max_gap(1).len(), 4);
+    }
+
+    #[test]
+    fn test_solve_with_max_gap() {
+        // 12 persons available from monday 6th to sunday 19th, i.e. 56 days / events
+        let content = all_available(
+            &["A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L"],
+            &(6..=19).collect::<Vec<u8>>(),
+        );
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.make_calendar(0, false);
+        let gaps_without_bias = calendar_maker.check_max_gap(3);
+
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let gaps = calendar_maker.solve_with_max_gap(3, 0);
+        assert!(calendar_maker.empty_slots().is_empty());
+        assert_eq!(gaps, calendar_maker.check_max_gap(3));
+        assert!(gaps.len() < gaps_without_bias.len());
+        assert!(calendar_maker.max_gap.is_none());
+    }
+
+    #[test]
+    fn test_solve_for_person_pool() {
+        // Monday 6th and tuesday 7th, with a pool of two persons per event
+        let content = "JANVIER,2025,6,7\r\n\
+            A,1ère SF jour,,\r\n\
+            B,1ère SF jour,,\r\n\
+            C,1ère SF nuit,,\r\n\
+            D,1ère SF nuit,,\r\n\
+            E,2ème SF jour,,\r\n\
+            F,2ème SF jour,,\r\n\
+            G,2ème SF nuit,,\r\n\
+            H,2ème SF nuit,,\r\n";
+        let calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let calendar = calendar_maker
+            .solve_for_person_pool(&["A", "B", "C", "D", "E", "F", "G", "H", "Z"])
+            .unwrap();
+        assert!(CalendarMaker::empty_slots_of(&calendar).is_empty());
+        assert_eq!(calendar_maker.empty_slots().len(), 2 * 4);
+
+        // Without B, nobody is available for the 1st daily on one of the days
+        let Err(ScheduleError::Unfilled(unfilled)) =
+            calendar_maker.solve_for_person_pool(&["A", "C", "D", "E", "F", "G", "H"])
+        else {
+            panic!("A can't be on-call two days in a row");
+        };
+        // The calendar is left empty when no solution is found
+        assert_eq!(unfilled.len(), 2 * 4);
+        assert_eq!(calendar_maker.person_count(), 8);
+    }
+
+    #[test]
+    fn test_take_initial_allocations() {
+        let content =
+            "JANVIER,2025,1,2,3,4,5\r\nAlice,1ère SF jour,,1,,x,x\r\nAlice,1ère SF nuit,x,,,x,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.take_initial_allocations(content.lines());
+        assert!(
+            calendar_maker
+                .calendar
+                .get_for(&Date::from_ordinal_date(2025, 2).unwrap(), &FirstDaily)
+                == Some(&"Alice".to_string())
+        );
+        // Because she's already on call the 2nd day, she's not available anymore the 1st and 3rd day
+        assert!(
+            calendar_maker
+                .availabilities
+                .get("Alice")
+                .unwrap()
+                .get(&calendar_maker.calendar.from())
+                .unwrap()
+                == &vec![]
+        );
+        assert!(
+            calendar_maker
+                .availabilities
+                .get("Alice")
+                .unwrap()
+                .get(&Date::from_ordinal_date(2025, 3).unwrap())
+                .unwrap()
+                == &vec![]
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        // Alice has one more availability than days in the calendar
+        let content = "JANVIER,2025,1,2,3\r\nAlice,1ère SF jour,,x,,\r\nBob,1ère SF jour,x,x,x\r\n";
+        let calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let unfillable_slots = calendar_maker.report_infeasible_cells();
+        assert!(unfillable_slots.contains(&(Date::from_ordinal_date(2025, 2).unwrap(), FirstDaily)));
+        assert!(
+            !unfillable_slots.contains(&(Date::from_ordinal_date(2025, 1).unwrap(), FirstDaily))
+        );
+        // Nobody is available for the other events at all
+        assert_eq!(unfillable_slots.len(), 1 + 3 * 3);
+        let warnings = calendar_maker.validate();
+        assert!(warnings.contains(&"Bob is never available".to_string()));
+        assert!(warnings.contains(
+            &"Alice: 1 availabilities after 2025-01-03 are ignored for FirstDaily".to_string()
+        ));
+        assert!(!warnings.contains(&"Alice is never available".to_string()));
+    }
+
+    #[test]
+    fn test_make_calendar_report() {
+        // Nobody is available for the 1st daily on monday 6th
+        let content = all_available(&["A", "B", "C", "D", "E", "F", "G", "H"], &[6, 7])
+            .replace("1ère SF jour,,", "1ère SF jour,x,");
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let report = calendar_maker.make_calendar_report(1);
+        assert_eq!(report.stats.unfilled, 0);
+        assert_eq!(report.subcontractors, vec!["EXT-0".to_string()]);
+        let day_6 = Date::from_calendar_date(2025, time::Month::January, 6).unwrap();
+        assert_eq!(
+            report.calendar.get_for(&day_6, &FirstDaily),
+            Some(&"EXT-0".to_string())
+        );
+        assert!(report.availabilities.contains_key("EXT-0"));
+        assert!(report.fairness_score > 0.0);
+
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["subcontractors"][0], "EXT-0");
+        assert_eq!(
+            json["calendar"]["days"]["2025-01-06"]["FirstDaily"],
+            "EXT-0"
+        );
+        assert_eq!(json["stats"]["unfilled"], 0);
+    }
+
+    #[test]
+    fn test_solve_with_budget_constraint() {
+        // Nobody is available for the 1st daily on monday 6th and tuesday 7th
+        let content = all_available(
+            &["A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L"],
+            &[6, 7, 8],
+        )
+        .replace("1ère SF jour,,,", "1ère SF jour,x,x,");
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let day_6 = Date::from_calendar_date(2025, time::Month::January, 6).unwrap();
+        let day_7 = day_6.next_day().unwrap();
+        assert_eq!(
+            calendar_maker.solve_with_budget_constraint(1),
+            Err(ScheduleError::Unfilled(vec![(day_7, FirstDaily)]))
+        );
+        assert_eq!(calendar_maker.person_count(), 12);
+        assert_eq!(calendar_maker.empty_slots().len(), 3 * 4);
+
+        assert_eq!(calendar_maker.solve_with_budget_constraint(2), Ok(2));
+        assert!(calendar_maker.empty_slots().is_empty());
+        assert_eq!(
+            calendar_maker.calendar().get_for(&day_6, &FirstDaily),
+            Some(&"EXT-0".to_string())
+        );
+        assert_eq!(
+            calendar_maker.calendar().get_for(&day_7, &FirstDaily),
+            Some(&"EXT-1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_solve_with_feedback_loop() {
+        // Nobody is available for the 1st daily on monday 6th and tuesday 7th
+        let content = all_available(
+            &["A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L"],
+            &[6, 7, 8],
+        )
+        .replace("1ère SF jour,,,", "1ère SF jour,x,x,");
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let day_6 = Date::from_ordinal_date(2025, 6).unwrap();
+        let day_7 = Date::from_ordinal_date(2025, 7).unwrap();
+        let Err(ScheduleError::Unfilled(unfilled)) = calendar_maker.solve_with_feedback_loop(1)
+        else {
+            panic!("Two subcontractors are needed");
+        };
+        assert!(!unfilled.is_empty());
+        assert_eq!(calendar_maker.empty_slots().len(), 3 * 4);
+        // The subcontractor added by the failed search is removed
+        assert_eq!(calendar_maker.person_count(), 12);
+        assert!(!calendar_maker
+            .persons
+            .keys()
+            .any(|name| name.starts_with(SUBCONTRACTOR_PREFIX)));
+        assert!(calendar_maker.subco_explanations.is_empty());
+
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        assert_eq!(calendar_maker.solve_with_feedback_loop(2), Ok(()));
+        assert!(calendar_maker.empty_slots().is_empty());
+        assert!(calendar_maker
+            .check_consecutive_day_rule_violations()
+            .is_empty());
+        let subcontractors = [day_6, day_7].map(|day| {
+            calendar_maker
+                .calendar
+                .get_for(&day, &FirstDaily)
+                .unwrap()
+                .clone()
+        });
+        assert_eq!(
+            subcontractors.iter().sorted().collect::<Vec<_>>(),
+            ["EXT-0", "EXT-1"]
+        );
+        assert_eq!(calendar_maker.subco_explanations.len(), 2);
+    }
+
+    #[test]
+    fn test_coverage_report() {
+        // The 6th is fully allocated, only the 2nd nightly is missing on the 7th, nothing is allocated on the 8th
+        let content = "JANVIER,2025,6,7,8\r\n\
+            A,1ère SF jour,1,x,x\r\n\
+            B,1ère SF nuit,1,1,x\r\n\
+            C,2ème SF jour,1,1,x\r\n\
+            D,2ème SF nuit,1,x,x\r\n\
+            E,1ère SF jour,x,1,x\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.take_initial_allocations(content.lines());
+        assert_eq!(calendar_maker.coverage_report(), CoverageReport::default());
+
+        // Nobody is available for the remaining days / events, the calendar is left as is
+        let day = |ordinal| Date::from_ordinal_date(2025, ordinal).unwrap();
+        assert_eq!(
+            calendar_maker.try_make_calendar(0, false),
+            Err(ScheduleError::Infeasible(vec![
+                (day(7), Event::SecondNightly),
+                (day(8), FirstDaily),
+                (day(8), FirstNightly),
+                (day(8), Event::SecondDaily),
+                (day(8), Event::SecondNightly),
+            ]))
+        );
+        let report = calendar_maker.coverage_report();
+        assert_eq!(report.fully_covered_days, Some(1));
+        assert_eq!(report.days_with_one_unfilled, Some(1));
+        assert_eq!(report.days_with_several_unfilled, Some(1));
+        let per_event = report.per_event.unwrap();
+        assert_eq!(
+            per_event[&FirstDaily],
+            EventCoverage {
+                planned: 3,
+                filled: 2
+            }
+        );
+        assert_eq!(
+            per_event[&Event::SecondNightly],
+            EventCoverage {
+                planned: 3,
+                filled: 1
+            }
+        );
+
+        // Whatever the solver
+        let solvers: [fn(&mut CalendarMaker); 5] = [
+            |calendar_maker| {
+                calendar_maker.solve_with_partial_result(0);
+            },
+            |calendar_maker| {
+                let _ = calendar_maker.solve_with_event_groups(&[]);
+            },
+            |calendar_maker| {
+                let _ = calendar_maker.solve_with_feedback_loop(0);
+            },
+            |calendar_maker| {
+                let _ = calendar_maker.solve_multi_pass();
+            },
+            |calendar_maker| {
+                let _ = calendar_maker.solve_events_independently();
+            },
+        ];
+        for solve in solvers {
+            let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+            calendar_maker.take_initial_allocations(content.lines());
+            solve(&mut calendar_maker);
+            assert_eq!(calendar_maker.coverage_report().fully_covered_days, Some(1));
+        }
+    }
+
+    #[test]
+    fn test_solve_n_times() {
+        let content = all_available(
+            &["A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L"],
+            &[6, 7, 8],
+        );
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.set_seed(42);
+        let solutions = calendar_maker.solve_n_times(5, 0);
+        assert!(solutions.len() > 1);
+        assert!(solutions.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        for (i, (calendar, _)) in solutions.iter().enumerate() {
+            assert!(solutions[i + 1..]
+                .iter()
+                .all(|(other, _)| other != calendar));
+            assert!(calendar.get_empty_days(&FirstDaily).is_empty());
+        }
+        assert_eq!(calendar_maker.empty_slots().len(), 3 * 4);
+
+        // Reproducible with the same seed
+        calendar_maker.set_seed(42);
+        let again = calendar_maker.solve_n_times(5, 0);
+        assert_eq!(
+            again
+                .iter()
+                .map(|(calendar, _)| calendar)
+                .collect::<Vec<_>>(),
+            solutions
+                .iter()
+                .map(|(calendar, _)| calendar)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_solve_pareto() {
+        let content = all_available(
+            &["A", "B", "C", "D", "E", "F", "G", "H", "I", "J"],
+            &[6, 7, 8],
+        );
+        let calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        // 12 on-calls for 10 persons: 2 of them are on-call twice, unless subcontractors take over
+        let frontier = calendar_maker.solve_pareto(3);
+        assert_eq!(frontier.first().unwrap().subco_days, 0);
+        assert!(frontier.first().unwrap().fairness_score > 0.0);
+        assert_eq!(frontier.last().unwrap().subco_days, 2);
+        assert_eq!(frontier.last().unwrap().fairness_score, 0.0);
+        assert!(frontier
+            .windows(2)
+            .all(|pair| pair[0].fairness_score > pair[1].fairness_score));
+        assert_eq!(calendar_maker.empty_slots().len(), 3 * 4);
+    }
+
+    #[test]
+    fn test_explain_subcontractor_need() {
+        // Only A is available for the 1st daily, on monday 6th and tuesday 7th
+        let content = all_available(&["A", "B", "C", "D", "E", "F", "G", "H"], &[6, 7])
+            .replace("1ère SF jour,,", "1ère SF jour,x,x")
+            .replace("A,1ère SF jour,x,x", "A,1ère SF jour,,");
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.make_calendar(1, false);
+        assert!(calendar_maker.empty_slots().is_empty());
+        assert_eq!(
+            calendar_maker.explain_subcontractor_need(0),
+            "EXT-0 was added because 2025-01-06 / FirstDaily had 0 available persons after A was assigned on adjacent days."
+        );
+        assert_eq!(
+            calendar_maker.explain_subcontractor_need(1),
+            "EXT-1 was not added"
+        );
+    }
+
+    #[test]
+    fn test_simulate_absence() {
+        // Only A and B are available for the 1st daily
+        let content = all_available(&["A", "B", "C", "D", "E", "F", "G", "H"], &[6, 7])
+            .replace("1ère SF jour,,", "1ère SF jour,x,x")
+            .replace("A,1ère SF jour,x,x", "A,1ère SF jour,,")
+            .replace("B,1ère SF jour,x,x", "B,1ère SF jour,,");
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.make_calendar(1, false);
+        let day_6 = Date::from_ordinal_date(2025, 6).unwrap();
+        let day_7 = Date::from_ordinal_date(2025, 7).unwrap();
+
+        // B can't be on-call both days
+        let result = calendar_maker
+            .simulate_absence("A", &[day_6, day_7])
+            .unwrap();
+        assert!(result.feasible);
+        assert_eq!(result.subcontractors_needed, 1);
+        assert!(result
+            .problematic
+            .iter()
+            .all(|(_, event)| *event == FirstDaily));
+        assert!(result.fairness_delta > 0.0);
+        // The calendar maker itself is left untouched
+        assert_eq!(calendar_maker.person_count(), 8);
+
+        // The other on-calls are kept, so nothing changes when she isn't on-call these days
+        let (off_day, _) = calendar_maker.calendar.assignments_for_person("C")[0];
+        let other_day = if off_day == day_6 { day_7 } else { day_6 };
+        let result = calendar_maker.simulate_absence("C", &[other_day]).unwrap();
+        assert_eq!(
+            result,
+            SimulationResult {
+                feasible: true,
+                subcontractors_needed: 0,
+                problematic: Vec::new(),
+                fairness_delta: 0.0,
+            }
+        );
+        // The 8 persons are on-call once, on one of the two days, so nobody else can replace her
+        let result = calendar_maker.simulate_absence("C", &[off_day]).unwrap();
+        assert!(result.feasible);
+        assert_eq!(result.subcontractors_needed, 1);
+        assert!(matches!(
+            calendar_maker.simulate_absence("Z", &[day_6]),
+            Err(AubepineError::UnknownPerson(_))
+        ));
+    }
+
+    #[test]
+    fn test_compute_minimum_team_size() {
+        // Only A is available for the 1st daily, she can't be on-call both days
+        let content = all_available(&["A", "B", "C", "D", "E", "F", "G", "H"], &[6, 7])
+            .replace("1ère SF jour,,", "1ère SF jour,x,x")
+            .replace("A,1ère SF jour,x,x", "A,1ère SF jour,,");
+        let calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        assert_eq!(calendar_maker.compute_minimum_team_size(0), 9);
+        assert_eq!(calendar_maker.compute_minimum_team_size(1), 8);
+        assert_eq!(calendar_maker.person_count(), 8);
+
+        // The new persons are employees like the others, available for everything
+        let mut calendar_maker = calendar_maker.clone_without_assignments();
+        calendar_maker.add_full_time_persons(2);
+        assert_eq!(calendar_maker.person_count(), 10);
+        assert_eq!(calendar_maker.persons().len(), 10);
+        let new_person = &calendar_maker.persons["NEW-1"];
+        assert_eq!(new_person.membership(), Membership::Employee);
+        let day_7 = Date::from_ordinal_date(2025, 7).unwrap();
+        assert_eq!(
+            calendar_maker.initial_availabilities["NEW-1"].get(&day_7),
+            Some(&Event::ALL.to_vec())
+        );
+    }
+
+    #[test]
+    fn test_solve_with_partial_result() {
+        // Nobody is available for the 1st daily on monday 6th
+        let content = all_available(&["A", "B", "C", "D", "E", "F", "G", "H"], &[6, 7])
+            .replace("1ère SF jour,,", "1ère SF jour,x,");
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let result = calendar_maker.solve_with_partial_result(0);
+        let day_6 = Date::from_calendar_date(2025, time::Month::January, 6).unwrap();
+        let day_7 = day_6.next_day().unwrap();
+        // The 1st daily can't be allocated, but the other events are
+        assert_eq!(
+            result.unfilled,
+            vec![(day_6, FirstDaily), (day_7, FirstDaily)]
+        );
+        assert_eq!(result.subcontractors_added, 0);
+        assert_eq!(calendar_maker.empty_slots().len(), 2 * 4);
+
+        let result = calendar_maker.solve_with_partial_result(1);
+        assert!(result.unfilled.is_empty());
+        assert_eq!(result.subcontractors_added, 1);
+        assert!(calendar_maker.empty_slots().is_empty());
+    }
+
+    #[test]
+    fn test_solve_multi_pass() {
+        let content = all_available(
+            &["A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L"],
+            &[6, 7, 8],
+        );
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        assert_eq!(calendar_maker.solve_multi_pass(), Ok(()));
+        assert!(calendar_maker.empty_slots().is_empty());
+        assert!(calendar_maker
+            .check_consecutive_day_rule_violations()
+            .is_empty());
+        // 12 events shared by 12 persons
+        let counts = calendar_maker.calendar.on_call_count_per_person();
+        assert!(counts.values().all(|count| *count == 1));
+
+        // Nobody is available for the 1st nightly on the 7th, so the First level can't be filled
+        let content = content.replace("1ère SF nuit,,,", "1ère SF nuit,,x,");
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let Err(ScheduleError::Unfilled(unfilled)) = calendar_maker.solve_multi_pass() else {
+            panic!("The First level can't be filled");
+        };
+        assert_eq!(unfilled.len(), 3 * 2);
+        assert!(unfilled.iter().all(|(_, event)| event.is_first_level()));
+        assert_eq!(calendar_maker.empty_slots().len(), 3 * 4);
+    }
+
+    #[test]
+    fn test_solve_with_event_groups() {
+        // Saturday 4th and sunday 5th, A is the only one available for the 2nd daily on the 4th, and may be on-call
+        // for the 2nd nightly on the 5th too, as it's the weekend
+        let content = "JANVIER,2025,4,5\r\n\
+            A,2ème SF jour,,x\r\n\
+            A,2ème SF nuit,x,\r\n\
+            B,2ème SF nuit,,x\r\n\
+            C,2ème SF jour,x,\r\n\
+            D,1ère SF jour,,x\r\n\
+            E,1ère SF jour,x,\r\n\
+            F,1ère SF nuit,,x\r\n\
+            G,1ère SF nuit,x,\r\n\
+            H,2ème SF nuit,x,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let second_level: &[Event] = &[Event::SecondDaily, Event::SecondNightly];
+        assert_eq!(
+            calendar_maker.solve_with_event_groups(&[second_level]),
+            Ok(())
+        );
+        let day_4 = Date::from_ordinal_date(2025, 4).unwrap();
+        let day_5 = Date::from_ordinal_date(2025, 5).unwrap();
+        assert_eq!(
+            calendar_maker.calendar.get_for(&day_4, &Event::SecondDaily),
+            Some(&"A".to_string())
+        );
+        assert_eq!(
+            calendar_maker
+                .calendar
+                .get_for(&day_5, &Event::SecondNightly),
+            Some(&"H".to_string())
+        );
+        assert!(calendar_maker.event_groups.is_empty());
+
+        // Without H, only A is available for the 2nd nightly on the 5th
+        let content = content.replace("H,2ème SF nuit,x,\r\n", "");
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let Err(ScheduleError::Unfilled(unfilled)) =
+            calendar_maker.solve_with_event_groups(&[second_level])
+        else {
+            panic!("A can't be on-call for the Second level two days in a row");
+        };
+        assert!(!unfilled.is_empty());
+        assert_eq!(calendar_maker.empty_slots().len(), 2 * 4);
+        // Without the group, the weekend lets A be on-call both days
+        assert_eq!(
+            calendar_maker.clone().solve_with_partial_result(0).unfilled,
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_solve_with_day_off_after_night() {
+        // Saturday 4th and sunday 5th, A is the only one available for the 2nd nightly on the 4th, and may be on-call
+        // for the 2nd daily on the 5th too, as it's the weekend
+        let content = "JANVIER,2025,4,5\r\n\
+            A,2ème SF nuit,,x\r\n\
+            A,2ème SF jour,x,\r\n\
+            B,2ème SF jour,,x\r\n\
+            C,2ème SF nuit,x,\r\n\
+            D,1ère SF jour,,x\r\n\
+            E,1ère SF jour,x,\r\n\
+            F,1ère SF nuit,,x\r\n\
+            G,1ère SF nuit,x,\r\n";
+        let day_5 = Date::from_ordinal_date(2025, 5).unwrap();
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.make_calendar(0, false);
+        assert_eq!(
+            calendar_maker.calendar.get_for(&day_5, &Event::SecondDaily),
+            Some(&"A".to_string())
+        );
+
+        // 12 hours of rest after the night
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.solve_with_day_off_after_night(12, 0);
+        assert_eq!(calendar_maker.empty_slots().len(), 2 * 4);
+        let content = format!("{}H,2ème SF jour,x,\r\n", content);
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.solve_with_day_off_after_night(12, 0);
+        assert_eq!(
+            calendar_maker.calendar.get_for(&day_5, &Event::SecondDaily),
+            Some(&"H".to_string())
+        );
+        assert_eq!(calendar_maker.rest_hours_after_night, 0);
+    }
+
+    #[test]
+    fn test_block_rest_after_night() {
+        let content = "JANVIER,2025,3,4,5\r\n\
+            A,2ème SF jour,,,\r\n\
+            A,2ème SF nuit,,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let day_3 = Date::from_ordinal_date(2025, 3).unwrap();
+        let day_4 = Date::from_ordinal_date(2025, 4).unwrap();
+        let day_5 = Date::from_ordinal_date(2025, 5).unwrap();
+        let availabilities = calendar_maker.availabilities["A"].clone();
+        for (rest_hours, expected) in [
+            (0, vec![Event::SecondDaily, Event::SecondNightly]),
+            (12, vec![Event::SecondNightly]),
+            (13, vec![]),
+        ] {
+            calendar_maker.rest_hours_after_night = rest_hours;
+            let mut her_availabilities = availabilities.clone();
+            calendar_maker.block_rest_after_night(
+                &mut her_availabilities,
+                day_3,
+                Event::SecondNightly,
+            );
+            assert_eq!(her_availabilities.get(&day_4), Some(&expected));
+            assert_eq!(her_availabilities.get(&day_3), availabilities.get(&day_3));
+            // The nights before the event are checked too
+            let mut her_availabilities = availabilities.clone();
+            calendar_maker.block_rest_after_night(
+                &mut her_availabilities,
+                day_5,
+                Event::SecondDaily,
+            );
+            let is_available_the_night_before = her_availabilities
+                .get(&day_4)
+                .unwrap()
+                .contains(&Event::SecondNightly);
+            assert_eq!(is_available_the_night_before, rest_hours == 0);
+        }
+    }
+
+    #[test]
+    fn test_block_holiday_rest() {
+        let content = "JANVIER,2025,1,2,3,4,5\r\nA,1ère SF jour,,,,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let day = |day| Date::from_ordinal_date(2025, day).unwrap();
+        calendar_maker.holidays = vec![day(1)];
+        let availabilities = calendar_maker.availabilities["A"].clone();
+        let available_days = |her_availabilities: &Availabilities| {
+            (1..=5)
+                .filter(|d| !her_availabilities.get(&day(*d)).unwrap().is_empty())
+                .collect::<Vec<u16>>()
+        };
+
+        // On-call on new year's day, she rests on the 2nd and the 3rd
+        let mut her_availabilities = availabilities.clone();
+        calendar_maker.block_holiday_rest(&mut her_availabilities, day(1));
+        assert_eq!(available_days(&her_availabilities), vec![1, 4, 5]);
+        // On-call on the 3rd, she can't be on-call on new year's day
+        let mut her_availabilities = availabilities.clone();
+        calendar_maker.block_holiday_rest(&mut her_availabilities, day(3));
+        assert_eq!(available_days(&her_availabilities), vec![2, 3, 4, 5]);
+        // The 4th is too far
+        let mut her_availabilities = availabilities.clone();
+        calendar_maker.block_holiday_rest(&mut her_availabilities, day(4));
+        assert_eq!(available_days(&her_availabilities), vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_solve_with_holiday_bonus_days() {
+        // From wednesday 1st to sunday 5th, new year's day is a public holiday
+        let content = all_available(
+            &["A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L"],
+            &[1, 2, 3, 4, 5],
+        );
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let day_1 = Date::from_ordinal_date(2025, 1).unwrap();
+        let day_3 = Date::from_ordinal_date(2025, 3).unwrap();
+        calendar_maker.solve_with_holiday_bonus_days(&[day_1], 0);
+        assert!(calendar_maker.empty_slots().is_empty());
+        for name in calendar_maker
+            .calendar
+            .events_on_day(&day_1)
+            .unwrap()
+            .values()
+        {
+            assert!(!CalendarMaker::is_on_call(
+                calendar_maker.calendar.events_on_day(&day_3).unwrap(),
+                name
+            ));
+        }
+        assert!(calendar_maker.holidays.is_empty());
     }
 
-    /// Returns true if the day is in the week-end (saturday or sunday)
-    fn is_weekend(day: Date) -> bool {
-        day.weekday() == time::Weekday::Saturday || day.weekday() == time::Weekday::Sunday
+    #[test]
+    fn test_solve_avoiding_same_person_for_day_and_night() {
+        // Saturday 4th and sunday 5th, A is the only one available for the 2nd daily on the 4th, and may be on-call
+        // for the 2nd nightly too, as it's the weekend
+        let content = "JANVIER,2025,4,5\r\n\
+            A,2ème SF jour,,x\r\n\
+            A,2ème SF nuit,,x\r\n\
+            B,1ère SF jour,,x\r\n\
+            C,1ère SF nuit,,x\r\n\
+            E,1ère SF jour,x,\r\n\
+            F,1ère SF nuit,x,\r\n\
+            G,2ème SF jour,x,\r\n\
+            H,2ème SF nuit,x,\r\n";
+        let day_4 = Date::from_ordinal_date(2025, 4).unwrap();
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.make_calendar(0, false);
+        assert!(calendar_maker.empty_slots().is_empty());
+
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.solve_avoiding_same_person_for_day_and_night(0);
+        assert_eq!(calendar_maker.empty_slots().len(), 2 * 4);
+        let content = format!("{}D,2ème SF nuit,,x\r\n", content);
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.solve_avoiding_same_person_for_day_and_night(0);
+        assert_eq!(
+            calendar_maker.calendar.get_for(&day_4, &Event::SecondDaily),
+            Some(&"A".to_string())
+        );
+        assert_eq!(
+            calendar_maker
+                .calendar
+                .get_for(&day_4, &Event::SecondNightly),
+            Some(&"D".to_string())
+        );
+        assert!(!calendar_maker.avoid_same_day_double);
+    }
+
+    #[test]
+    fn test_solve_with_senior_night_pairing() {
+        // Monday 6th and tuesday 7th, only Sam is senior
+        let content = all_available(&["A", "B", "C", "D", "E", "F", "G", "H", "Sam"], &[6, 7]);
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker
+            .person_mut("Sam")
+            .unwrap()
+            .set_seniority(Seniority::Senior);
+        let day_6 = Date::from_ordinal_date(2025, 6).unwrap();
+        let day_7 = Date::from_ordinal_date(2025, 7).unwrap();
+        // Sam can only be on-call one of the two days
+        let unpaired = calendar_maker.solve_with_senior_night_pairing(0);
+        assert!(calendar_maker.empty_slots().is_empty());
+        assert_eq!(unpaired.len(), 1);
+        assert_eq!(unpaired, calendar_maker.check_senior_night_pairing());
+        let sam_day = calendar_maker.calendar.assignments_for_person("Sam")[0].0;
+        assert!(
+            unpaired == vec![day_6] && sam_day == day_7
+                || unpaired == vec![day_7] && sam_day == day_6
+        );
+        assert!(!calendar_maker.senior_night_pairing);
+
+        // With A as an expert, a senior person can be on-call each day
+        calendar_maker
+            .person_mut("A")
+            .unwrap()
+            .set_seniority(Seniority::Expert);
+        let mut calendar_maker = calendar_maker.clone_without_assignments();
+        assert_eq!(calendar_maker.solve_with_senior_night_pairing(0), vec![]);
+    }
+
+    #[test]
+    fn test_solve_with_fixed_rotation() {
+        // From monday 6th to saturday 11th, Bob isn't available on the 7th
+        let mut content = "JANVIER,2025,6,7,8,9,10,11\r\n\
+            Alice,1ère SF jour,,,,,,\r\n\
+            Bob,1ère SF jour,,x,,,,\r\n\
+            Charlie,1ère SF jour,,,,,,\r\n"
+            .to_string();
+        for name in ["D", "E", "F", "G", "H", "I", "J", "K"] {
+            for event in [FirstNightly, Event::SecondDaily, Event::SecondNightly] {
+                let label = event.to_label(Locale::default());
+                content.push_str(&format!("{},{},,,,,,\r\n", name, label));
+            }
+        }
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let rotation = ["Alice", "Bob", "Charlie"].map(String::from);
+        calendar_maker.solve_with_fixed_rotation(&rotation, FirstDaily, 0);
+        assert!(calendar_maker.empty_slots().is_empty());
+        let first_daily = calendar_maker
+            .calendar
+            .get_all()
+            .values()
+            .map(|events| events[&FirstDaily].as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            first_daily,
+            vec!["Alice", "Charlie", "Alice", "Bob", "Charlie", "Alice"]
+        );
+        assert_eq!(calendar_maker.export_rotation(FirstDaily), first_daily);
+
+        // Bob isn't qualified for the 1st daily, the rotation skips him
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.set_qualified_events("Bob", &[Event::SecondDaily]);
+        calendar_maker.solve_with_fixed_rotation(&rotation, FirstDaily, 0);
+        assert_eq!(
+            calendar_maker.export_rotation(FirstDaily),
+            vec!["Alice", "Charlie", "Alice", "Charlie", "Alice", "Charlie"]
+        );
+
+        // Nobody is available for the 2nd nightly on the 11th, the calendar is left untouched
+        let content = content.replace("2ème SF nuit,,,,,,", "2ème SF nuit,,,,,,x");
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.solve_with_fixed_rotation(&rotation, FirstDaily, 0);
+        assert_eq!(calendar_maker.empty_slots().len(), 6 * 4);
     }
 
-    /// Return the days with the least availabilities for the event passed in argument
-    fn get_days_with_least_availabilities(
-        availabilities: &AvailabilitiesPerPerson,
-        within_days: &[Date],
-        event: Event,
-    ) -> Vec<(Date, Vec<Name>)> {
-        let mut availabilities_per_day = HashMap::new();
-        let mut days_per_availabilities = HashMap::new();
-        // Sorting the days allow to have a deterministic result
-        for day in within_days.iter().sorted() {
-            let mut persons = Vec::new();
-            for (name, availabilities) in availabilities {
-                if availabilities
-                    .get(day)
-                    .and_then(|a| a.iter().find(|e| *e == &event))
-                    .is_some()
-                {
-                    persons.push(name.to_string());
-                }
-            }
-            let persons_len = persons.len();
-            availabilities_per_day.insert(day, persons);
-            days_per_availabilities
-                .entry(persons_len)
-                .and_modify(|d: &mut Vec<&Date>| d.push(day))
-                .or_insert(vec![day]);
-        }
-        let &least = days_per_availabilities.keys().min().expect("No day found");
-        let mut days_and_names = Vec::new();
-        for &day in days_per_availabilities.get(&least).unwrap() {
-            let names = availabilities_per_day.get(day).unwrap();
-            // Sorting the names allow to have a deterministic result
-            let sorted_names = names.iter().cloned().sorted().collect();
-            days_and_names.push((*day, sorted_names));
-        }
-        days_and_names
+    #[test]
+    fn test_export_rotation() {
+        let content = "JANVIER,2025,1,2,3\r\nAlice,1ère SF jour,,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        assert_eq!(
+            calendar_maker.export_rotation(FirstDaily),
+            Vec::<Name>::new()
+        );
+        let day = |day| Date::from_ordinal_date(2025, day).unwrap();
+        calendar_maker
+            .calendar
+            .set_for(day(3), FirstDaily, "Charlie".to_string());
+        calendar_maker
+            .calendar
+            .set_for(day(1), FirstDaily, "Bob".to_string());
+        calendar_maker
+            .calendar
+            .set_for(day(2), FirstNightly, "Alice".to_string());
+        assert_eq!(
+            calendar_maker.export_rotation(FirstDaily),
+            vec!["Bob", "Charlie"]
+        );
+        assert_eq!(calendar_maker.export_rotation(FirstNightly), vec!["Alice"]);
     }
 
-    fn from_lines(lines: &mut std::str::Lines) -> Self {
-        let first_line = lines.next().expect("Empty file!");
-        let mut month = None;
-        let mut year = None;
-        let mut first_day = None;
-        let mut last_day = None;
-        for (i, token) in first_line.split(&[',', ';']).enumerate() {
-            if i == 0 {
-                match token.to_ascii_uppercase().as_str() {
-                    "JANVIER" => month = Some(time::Month::January),
-                    "FEVRIER" => month = Some(time::Month::February),
-                    "MARS" => month = Some(time::Month::March),
-                    "AVRIL" => month = Some(time::Month::April),
-                    "MAI" => month = Some(time::Month::May),
-                    "JUIN" => month = Some(time::Month::June),
-                    "JUILLET" => month = Some(time::Month::July),
-                    "AOUT" => month = Some(time::Month::August),
-                    "SEPTEMBRE" => month = Some(time::Month::September),
-                    "OCTOBRE" => month = Some(time::Month::October),
-                    "NOVEMBRE" => month = Some(time::Month::November),
-                    "DECEMBRE" => month = Some(time::Month::December),
-                    _ => panic!("Invalid month"),
-                }
-            } else if i == 1 {
-                year = Some(token.parse().expect("Invalid year"));
-            } else if i == 2 {
-                first_day = Some(token.parse().expect("Invalid day"));
-            } else {
-                last_day = Some(token.parse().expect("Invalid day"));
-            }
+    #[test]
+    fn test_solve_events_independently() {
+        // Monday 6th and tuesday 7th, with a pool of two persons per event
+        let content = "JANVIER,2025,6,7\r\n\
+            A,1ère SF jour,,\r\n\
+            B,1ère SF jour,,\r\n\
+            C,1ère SF nuit,,\r\n\
+            D,1ère SF nuit,,\r\n\
+            E,2ème SF jour,,\r\n\
+            F,2ème SF jour,,\r\n\
+            G,2ème SF nuit,,\r\n\
+            H,2ème SF nuit,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        assert_eq!(calendar_maker.solve_events_independently(), Ok(()));
+        for events in calendar_maker.calendar.get_all().values() {
+            assert_eq!(events.len(), 4);
         }
-        let from =
-            Date::from_calendar_date(year.unwrap(), month.unwrap(), first_day.unwrap()).unwrap();
-        let to =
-            Date::from_calendar_date(year.unwrap(), month.unwrap(), last_day.unwrap()).unwrap();
-        let calendar = Calendar::new(from, to);
+        let pool = |event| {
+            calendar_maker
+                .calendar
+                .get_all()
+                .values()
+                .map(|events| events[&event].clone())
+                .sorted()
+                .collect::<Vec<Name>>()
+        };
+        assert_eq!(pool(FirstDaily), vec!["A", "B"]);
+        assert_eq!(pool(Event::SecondNightly), vec!["G", "H"]);
 
-        let mut availabilities = HashMap::new();
-        while let Some(line) = lines.next().as_mut() {
-            let (name, availabilities_str) = line.split_once([',', ';']).expect("Name missing");
-            availabilities
-                .entry(name.to_string())
-                .and_modify(|a: &mut Availabilities| a.merge(calendar.from(), availabilities_str))
-                .or_insert(Availabilities::from_str(
-                    calendar.from(),
-                    availabilities_str,
-                ));
-        }
+        // A is the only one available on the 6th both as 1st daily and 1st nightly
+        let content = content
+            .replace("C,1ère SF nuit,,", "C,1ère SF nuit,x,")
+            .replace("D,1ère SF nuit,,", "D,1ère SF nuit,x,")
+            .replace("A,1ère SF jour,,", "A,1ère SF jour,,\r\nA,1ère SF nuit,,x");
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let day_6 = Date::from_calendar_date(2025, time::Month::January, 6).unwrap();
+        assert_eq!(
+            calendar_maker.solve_events_independently(),
+            Err(ScheduleError::Unfilled(vec![(day_6, FirstNightly)]))
+        );
+    }
 
-        Self {
-            calendar,
-            availabilities,
-            problematic_days: BTreeMap::new(),
-            max_subcontractor: 0,
-            verbose: false,
-        }
+    #[test]
+    fn test_optimize_event_order() {
+        let content = "JANVIER,2025,1,2\r\n\
+            Alice,2ème SF nuit,,\r\n\
+            Alice,1ère SF nuit,,\r\n\
+            Bob,1ère SF nuit,,\r\n\
+            Bob,1ère SF jour,,x\r\n\
+            Charlie,2ème SF nuit,,\r\n";
+        let calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        assert_eq!(
+            calendar_maker.optimize_event_order(),
+            [
+                Event::SecondDaily,
+                FirstDaily,
+                FirstNightly,
+                Event::SecondNightly
+            ]
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::Event::{FirstDaily, FirstNightly};
+    #[test]
+    fn test_make_calendar_with_infeasible_cells() {
+        let content = "JANVIER,2025,1,2,3\r\nAlice,1ère SF jour,,x,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let infeasible_cells = calendar_maker.report_infeasible_cells();
+        assert_eq!(infeasible_cells.len(), 1 + 3 * 3);
+
+        // Not enough subcontractors, the search doesn't even start
+        calendar_maker.make_calendar(2, false);
+        assert!(calendar_maker.problematic_days.is_empty());
+        assert_eq!(calendar_maker.empty_slots().len(), 3 * 4);
+        assert_eq!(calendar_maker.report_infeasible_cells(), infeasible_cells);
+    }
 
     #[test]
-    fn test_from_lines() {
-        let content =
-            "JANVIER,2025,1,2,3,4,5\r\nAlice,1ère SF jour,,,,x,x\r\nAlice,1ère SF nuit,x,,,x,\r\n";
+    fn test_validate_low_coverage_density() {
+        let content = "JANVIER,2025,1,2,3,4,5,6,7,8,9,10,11\r\n\
+            Alice,1ère SF jour,,x,x,x,x,x,x,x,x,x,x\r\n\
+            Alice,1ère SF nuit,,,x,x,x,x,x,x,x,x,x\r\n";
         let calendar_maker = CalendarMaker::from_lines(&mut content.lines());
-        assert!(calendar_maker.calendar.from() == Date::from_ordinal_date(2025, 1).unwrap());
-        assert!(calendar_maker.calendar.get_all().len() == 5);
-        assert!(calendar_maker.availabilities.keys().any(|a| a == "Alice"));
+        let warnings = calendar_maker.validate();
         assert!(
-            calendar_maker
-                .availabilities
-                .get("Alice")
-                .unwrap()
-                .get(&calendar_maker.calendar.from())
-                .unwrap()
-                == &vec![FirstDaily]
+            warnings.contains(&"Alice is available only 9% of the days for FirstDaily".to_string())
         );
-        assert!(
-            calendar_maker
-                .availabilities
-                .get("Alice")
-                .unwrap()
-                .get(&Date::from_ordinal_date(2025, 5).unwrap())
-                .unwrap()
-                == &vec![FirstNightly]
+        assert!(!warnings
+            .iter()
+            .any(|w| w.starts_with("Alice is available only") && w.contains("FirstNightly")));
+    }
+
+    #[test]
+    fn test_available_persons_for() {
+        let content = "JANVIER,2025,1,2\r\nCharlie,1ère SF jour,,\r\nAlice,1ère SF jour,,x\r\nBob,1ère SF jour,x,\r\n";
+        let calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let day_1 = Date::from_ordinal_date(2025, 1).unwrap();
+        assert_eq!(
+            calendar_maker.available_persons_for(day_1, FirstDaily),
+            vec!["Alice", "Charlie"]
         );
+        assert!(calendar_maker
+            .available_persons_for(day_1, FirstNightly)
+            .is_empty());
     }
 
     #[test]
-    fn test_take_initial_allocations() {
+    fn test_days_with_only_one_available_person() {
         let content =
-            "JANVIER,2025,1,2,3,4,5\r\nAlice,1ère SF jour,,1,,x,x\r\nAlice,1ère SF nuit,x,,,x,\r\n";
+            "JANVIER,2025,1,2,3,4\r\nAlice,1ère SF jour,1,x,,x\r\nBob,1ère SF jour,,x,,\r\n";
         let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
         calendar_maker.take_initial_allocations(content.lines());
-        assert!(
-            calendar_maker
-                .calendar
-                .get_for(&Date::from_ordinal_date(2025, 2).unwrap(), &FirstDaily)
-                == Some(&"Alice".to_string())
+        // The 1st is already allocated, nobody is available on the 2nd and both are on the 3rd
+        assert_eq!(
+            calendar_maker.days_with_only_one_available_person(FirstDaily),
+            vec![Date::from_ordinal_date(2025, 4).unwrap()]
         );
-        // Because she's already on call the 2nd day, she's not available anymore the 1st and 3rd day
-        assert!(
-            calendar_maker
-                .availabilities
-                .get("Alice")
-                .unwrap()
-                .get(&calendar_maker.calendar.from())
-                .unwrap()
-                == &vec![]
+    }
+
+    #[test]
+    fn test_days_without_any_available_person() {
+        let content = "JANVIER,2025,1,2,3\r\nAlice,1ère SF jour,,x,\r\nBob,1ère SF jour,,x,x\r\n";
+        let calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        assert_eq!(
+            calendar_maker.days_without_any_available_person(FirstDaily),
+            vec![Date::from_ordinal_date(2025, 2).unwrap()]
         );
-        assert!(
+        assert_eq!(
             calendar_maker
-                .availabilities
-                .get("Alice")
-                .unwrap()
-                .get(&Date::from_ordinal_date(2025, 3).unwrap())
-                .unwrap()
-                == &vec![]
+                .days_without_any_available_person(FirstNightly)
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_add_subco_across_years() {
+        let from = Date::from_calendar_date(2024, time::Month::December, 30).unwrap();
+        let to = Date::from_calendar_date(2025, time::Month::January, 3).unwrap();
+        let calendar_maker = CalendarMaker::new(Calendar::new(from, to), HashMap::new());
+        let new_year = Date::from_ordinal_date(2025, 1).unwrap();
+        let availabilities = calendar_maker.add_subco_for_this_day_and_event(
+            &AvailabilitiesPerPerson::new(),
+            "EXT-0",
+            new_year,
+            Event::SecondNightly,
+        );
+        let subco_availabilities = availabilities.get("EXT-0").unwrap().get_all();
+        assert_eq!(subco_availabilities.len(), 5);
+        for (day, events) in subco_availabilities {
+            if *day == new_year {
+                assert_eq!(events, &vec![Event::SecondNightly]);
+            } else {
+                assert!(events.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_check_for_premature_stop() {
+        // Monday 6th and tuesday 7th, with only Alice available
+        let monday = Date::from_ordinal_date(2025, 6).unwrap();
+        let only_alice = vec!["Alice".to_string()];
+        let days_and_names = vec![
+            (monday, only_alice.clone()),
+            (monday.next_day().unwrap(), only_alice.clone()),
+        ];
+        assert!(CalendarMaker::check_for_premature_stop(
+            &days_and_names,
+            &FirstDaily
+        ));
+        assert!(CalendarMaker::check_for_premature_stop(
+            &days_and_names,
+            &Event::SecondDaily
+        ));
+
+        // Friday 10th and saturday 11th, she can be on-call both days for the Second level
+        let friday = Date::from_ordinal_date(2025, 10).unwrap();
+        let days_and_names = vec![
+            (friday, only_alice.clone()),
+            (friday.next_day().unwrap(), only_alice.clone()),
+        ];
+        assert!(!CalendarMaker::check_for_premature_stop(
+            &days_and_names,
+            &Event::SecondDaily
+        ));
+        assert!(!CalendarMaker::check_for_premature_stop(
+            &days_and_names,
+            &Event::SecondNightly
+        ));
+        assert!(CalendarMaker::check_for_premature_stop(
+            &days_and_names,
+            &FirstNightly
+        ));
+
+        // Not the same person
+        let days_and_names = vec![
+            (monday, only_alice),
+            (monday.next_day().unwrap(), vec!["Bob".to_string()]),
+        ];
+        assert!(!CalendarMaker::check_for_premature_stop(
+            &days_and_names,
+            &FirstDaily
+        ));
+    }
+
+    #[test]
+    fn test_scarcest_first() {
+        // Alice is available the 1st and 2nd days, Bob every day for any event
+        let content = "JANVIER,2025,1,2,3\r\n\
+            Alice,1ère SF jour,,,x\r\n\
+            Bob,1ère SF jour,,,\r\n\
+            Bob,2ème SF jour,,,\r\n\
+            Charlie,1ère SF jour,x,x,\r\n";
+        let calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let availabilities = &calendar_maker.availabilities;
+        let day_1 = Date::from_ordinal_date(2025, 1).unwrap();
+        let day_3 = Date::from_ordinal_date(2025, 3).unwrap();
+        let days_and_names = vec![
+            (day_3, vec!["Bob".to_string(), "Charlie".to_string()]),
+            (day_1, vec!["Alice".to_string(), "Bob".to_string()]),
+        ];
+        // Charlie is available only once
+        assert_eq!(
+            CalendarMaker::scarcest_first(availabilities, days_and_names.clone()),
+            days_and_names
+        );
+        let days_and_names = vec![
+            (day_3, vec!["Bob".to_string()]),
+            (day_1, vec!["Alice".to_string(), "Bob".to_string()]),
+        ];
+        assert_eq!(
+            CalendarMaker::scarcest_first(availabilities, days_and_names.clone()),
+            days_and_names.into_iter().rev().collect::<Vec<_>>()
         );
     }
 
@@ -574,9 +5057,10 @@ mod tests {
         let content = "JANVIER,2025,1,2,3\r\nAlice,1ère SF jour,,x,\r\nBob,1ère SF jour,,,x,\r\n";
         let calendar_maker = CalendarMaker::from_lines(&mut content.lines());
 
-        let (_, new_calendar, _, _) = CalendarMaker::find_next(
+        let (_, new_calendar, _, _) = calendar_maker.find_next(
             calendar_maker.availabilities.clone(),
             calendar_maker.calendar.clone(),
+            HashMap::new(),
             Event::FirstDaily,
             0,
         );
@@ -606,9 +5090,10 @@ mod tests {
         let content = "JANVIER,2025,1,2,3,4,5,6,7\r\nAlice,1ère SF jour,,,,,x,x,\r\nBob,1ère SF jour,x,x,,x,x,,\r\nCharlie,1ère SF jour,x,,x,x,,,x\r\n";
         let calendar_maker = CalendarMaker::from_lines(&mut content.lines());
 
-        let (_, new_calendar, _, _) = CalendarMaker::find_next(
+        let (_, new_calendar, _, _) = calendar_maker.find_next(
             calendar_maker.availabilities.clone(),
             calendar_maker.calendar.clone(),
+            HashMap::new(),
             Event::FirstDaily,
             0,
         );
@@ -623,14 +5108,218 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_balancing_strategy() {
+        let content = "JANVIER,2025,1,2,3,4\r\n\
+            Alice,1ère SF jour,1,,,\r\n\
+            Bob,1ère SF nuit,,1,,\r\n\
+            Bob,1ère SF jour,,,,\r\n\
+            Charlie,1ère SF jour,,,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.take_initial_allocations(content.lines());
+        let names = vec![
+            "Alice".to_string(),
+            "Bob".to_string(),
+            "Charlie".to_string(),
+        ];
+        let calendar = calendar_maker.calendar.clone();
+        let on_call_counts = CalendarMaker::on_call_counts(&calendar);
+        let day_4 = Date::from_ordinal_date(2025, 4).unwrap();
+        let sort = |calendar_maker: &CalendarMaker| {
+            calendar_maker.sort_names(&names, &on_call_counts, &calendar, day_4, FirstDaily)
+        };
+        assert_eq!(sort(&calendar_maker), vec!["Charlie", "Alice", "Bob"]);
+        calendar_maker.set_balancing_strategy(BalancingStrategy::LeastForEvent);
+        assert_eq!(sort(&calendar_maker), vec!["Bob", "Charlie", "Alice"]);
+        // Alice was the last one on-call for the 1st daily
+        calendar_maker.set_balancing_strategy(BalancingStrategy::RoundRobin);
+        assert_eq!(sort(&calendar_maker), vec!["Bob", "Charlie", "Alice"]);
+    }
+
+    #[test]
+    fn test_priority_balancing_strategy() {
+        // A single day, so that only 4 of the 8 persons are on-call
+        let content = all_available(&["A", "B", "C", "D", "E", "F", "G", "H"], &[6]);
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.make_calendar(0, false);
+        assert_eq!(
+            calendar_maker
+                .calendar
+                .on_call_count_per_person()
+                .into_keys()
+                .collect::<Vec<_>>(),
+            vec!["A", "B", "C", "D"]
+        );
+
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.set_balancing_strategy(BalancingStrategy::Priority);
+        for name in ["G", "H"] {
+            calendar_maker
+                .person_mut(name)
+                .unwrap()
+                .set_preference_weight(1);
+        }
+        calendar_maker.make_calendar(0, false);
+        assert!(calendar_maker.empty_slots().is_empty());
+        assert_eq!(
+            calendar_maker
+                .calendar
+                .on_call_count_per_person()
+                .into_keys()
+                .collect::<Vec<_>>(),
+            vec!["A", "B", "G", "H"]
+        );
+    }
+
+    #[test]
+    fn test_carry_over_balancing_strategy() {
+        let content = all_available(&["A", "B", "C", "D", "E", "F", "G", "H"], &[6]);
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.set_balancing_strategy(BalancingStrategy::CarryOver);
+        // A, B and C were on-call last month, D twice
+        let balance = HashMap::from([
+            ("A".to_string(), 1),
+            ("B".to_string(), 1),
+            ("C".to_string(), 1),
+            ("D".to_string(), 2),
+        ]);
+        calendar_maker.import_balance(&balance);
+        calendar_maker.make_calendar(0, false);
+        assert!(calendar_maker.empty_slots().is_empty());
+        assert_eq!(
+            calendar_maker
+                .calendar
+                .on_call_count_per_person()
+                .into_keys()
+                .collect::<Vec<_>>(),
+            vec!["E", "F", "G", "H"]
+        );
+    }
+
+    #[test]
+    fn test_tie_breaking_strategy() {
+        let content = "JANVIER,2025,1,2,3,4,5\r\n\
+            Charlie,1ère SF jour,1,,,,\r\n\
+            Bob,1ère SF jour,,,1,,\r\n\
+            Alice,1ère SF jour,,,,,\r\n\
+            Dave,1ère SF jour,,,,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.take_initial_allocations(content.lines());
+        let names = calendar_maker
+            .persons()
+            .into_iter()
+            .map(|name| name.to_string())
+            .rev()
+            .collect::<Vec<Name>>();
+        let calendar = calendar_maker.calendar.clone();
+        let day_5 = Date::from_ordinal_date(2025, 5).unwrap();
+        let break_ties =
+            |calendar_maker: &CalendarMaker| calendar_maker.break_ties(&names, &calendar, day_5);
+        assert_eq!(
+            break_ties(&calendar_maker),
+            vec!["Alice", "Bob", "Charlie", "Dave"]
+        );
+        calendar_maker.set_tie_breaking_strategy(TieBreakingStrategy::LastAssignedFirst);
+        assert_eq!(
+            break_ties(&calendar_maker),
+            vec!["Bob", "Charlie", "Alice", "Dave"]
+        );
+        calendar_maker.set_tie_breaking_strategy(TieBreakingStrategy::LastAssignedLast);
+        assert_eq!(
+            break_ties(&calendar_maker),
+            vec!["Alice", "Dave", "Charlie", "Bob"]
+        );
+        calendar_maker.set_tie_breaking_strategy(TieBreakingStrategy::Random(42));
+        let shuffled = break_ties(&calendar_maker);
+        assert_eq!(
+            shuffled.iter().sorted().collect::<Vec<_>>(),
+            vec!["Alice", "Bob", "Charlie", "Dave"]
+        );
+        assert_eq!(break_ties(&calendar_maker), shuffled);
+
+        // The tie-breaking strategy only applies between the persons with as many on-call days
+        let on_call_counts = CalendarMaker::on_call_counts(&calendar);
+        calendar_maker.set_tie_breaking_strategy(TieBreakingStrategy::LastAssignedFirst);
+        assert_eq!(
+            calendar_maker.sort_names(&names, &on_call_counts, &calendar, day_5, FirstDaily),
+            vec!["Alice", "Dave", "Bob", "Charlie"]
+        );
+    }
+
+    #[test]
+    fn test_first_assignment_strategy() {
+        let content = "JANVIER,2025,1,2,3,4,5\r\n\
+            Alice,1ère SF jour,,,,,\r\n\
+            Bob,1ère SF jour,1,,,,1\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let availabilities = calendar_maker.availabilities.clone();
+        let remaining_days = calendar_maker.calendar.get_empty_days(&Event::FirstDaily);
+        let days_and_names = CalendarMaker::get_days_with_least_availabilities(
+            &availabilities,
+            &remaining_days,
+            Event::FirstDaily,
+        );
+        let day = |ordinal| Date::from_ordinal_date(2025, ordinal).unwrap();
+        let first_days = |calendar_maker: &CalendarMaker| {
+            calendar_maker
+                .put_first_day_first(
+                    &availabilities,
+                    &remaining_days,
+                    Event::FirstDaily,
+                    days_and_names.clone(),
+                )
+                .into_iter()
+                .map(|(day, _)| day)
+                .collect::<Vec<Date>>()
+        };
+        assert_eq!(first_days(&calendar_maker), vec![day(1), day(5)]);
+        calendar_maker.set_first_assignment_strategy(FirstAssignmentStrategy::DeadlineFirst);
+        assert_eq!(first_days(&calendar_maker), vec![day(5), day(1)]);
+        calendar_maker.set_first_assignment_strategy(FirstAssignmentStrategy::EarliestFirst);
+        assert_eq!(first_days(&calendar_maker), vec![day(1), day(5)]);
+        calendar_maker.set_first_assignment_strategy(FirstAssignmentStrategy::Random(42));
+        let shuffled = first_days(&calendar_maker);
+        assert!(remaining_days.contains(&shuffled[0]));
+        assert_eq!(first_days(&calendar_maker), shuffled);
+    }
+
+    #[test]
+    fn test_solve_with_deadline_first() {
+        let content = all_available(
+            &["A", "B", "C", "D", "E", "F", "G", "H"],
+            &[6, 7, 8, 9, 10, 11, 12],
+        );
+        // Everybody is equally available, each day of the 1st daily goes to the first person not on-call yet
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.make_calendar(0, false);
+        assert_eq!(
+            calendar_maker.export_rotation(FirstDaily),
+            vec!["A", "B", "C", "D", "E", "F", "G"]
+        );
+
+        // The 12th is filled first, then the 11th, and so on
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.solve_with_deadline_first(0);
+        assert!(calendar_maker.empty_slots().is_empty());
+        assert_eq!(
+            calendar_maker.export_rotation(FirstDaily),
+            vec!["G", "F", "E", "D", "C", "B", "A"]
+        );
+        assert_eq!(
+            calendar_maker.first_assignment_strategy,
+            FirstAssignmentStrategy::MostConstrained
+        );
+    }
+
     #[test]
     fn test_sort_names_by_least_on_call() {
         let content = "JANVIER,2025,1,2,3,4,5,6,7\r\nAlice,1ère SF jour,,,,,x,x,\r\nBob,1ère SF jour,x,x,,x,x,,\r\nCharlie,1ère SF jour,x,,x,x,,,x\r\n";
         let calendar_maker = CalendarMaker::from_lines(&mut content.lines());
 
-        let (_, new_calendar, _, _) = CalendarMaker::find_next(
+        let (_, new_calendar, _, _) = calendar_maker.find_next(
             calendar_maker.availabilities.clone(),
             calendar_maker.calendar.clone(),
+            HashMap::new(),
             Event::FirstDaily,
             0,
         );
@@ -639,7 +5328,61 @@ mod tests {
             "Bob".to_string(),
             "Charlie".to_string(),
         ];
-        let sorted_names = CalendarMaker::sort_names_by_least_on_call(&names, &new_calendar);
+        let on_call_counts = CalendarMaker::on_call_counts(&new_calendar);
+        let sorted_names = calendar_maker.sort_names_by_least_on_call(&names, &on_call_counts);
         assert_eq!(sorted_names, vec!["Bob", "Charlie", "Alice"]);
     }
+
+    #[test]
+    fn test_sort_names_with_employees_first() {
+        let content = "JANVIER,2025,1,2\r\nAlice,1ère SF jour,,\r\nBob,1ère SF jour,,\r\nEXT-0,1ère SF jour,,\r\n";
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        let names = vec!["Alice".to_string(), "Bob".to_string(), "EXT-0".to_string()];
+        let on_call_counts = HashMap::from([
+            ("Alice".to_string(), 2),
+            ("Bob".to_string(), 1),
+            ("EXT-0".to_string(), 1),
+        ]);
+        assert_eq!(
+            calendar_maker.sort_names_by_least_on_call(&names, &on_call_counts),
+            vec!["Bob", "EXT-0", "Alice"]
+        );
+        calendar_maker.employees_first = true;
+        assert_eq!(
+            calendar_maker.sort_names_by_least_on_call(&names, &on_call_counts),
+            vec!["Bob", "Alice", "EXT-0"]
+        );
+        // Alice is on-call 2 more days than the subcontractor
+        let on_call_counts = HashMap::from([("Alice".to_string(), 2)]);
+        assert_eq!(
+            calendar_maker.sort_names_by_least_on_call(&names, &on_call_counts),
+            vec!["Bob", "EXT-0", "Alice"]
+        );
+    }
+
+    #[test]
+    fn test_solve_prioritizing_employees_over_subcontractors() {
+        // From wednesday 1st to friday 3rd, only Zoe and EXT-0 are available for the 1st daily
+        let mut content =
+            "JANVIER,2025,1,2,3\r\nZoe,1ère SF jour,,,\r\nEXT-0,1ère SF jour,,,\r\n".to_string();
+        for name in ["A", "B", "C", "D", "E", "F", "G", "H", "I"] {
+            for event in [FirstNightly, Event::SecondDaily, Event::SecondNightly] {
+                let label = event.to_label(Locale::default());
+                content.push_str(&format!("{},{},,,\r\n", name, label));
+            }
+        }
+        let on_call_count = |calendar_maker: &CalendarMaker, name: &str| {
+            calendar_maker.calendar.assignments_for_person(name).len()
+        };
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.make_calendar(0, false);
+        assert!(calendar_maker.empty_slots().is_empty());
+        assert_eq!(on_call_count(&calendar_maker, "EXT-0"), 2);
+
+        let mut calendar_maker = CalendarMaker::from_lines(&mut content.lines());
+        calendar_maker.solve_prioritizing_employees_over_subcontractors(0);
+        assert!(calendar_maker.empty_slots().is_empty());
+        assert_eq!(on_call_count(&calendar_maker, "Zoe"), 2);
+        assert!(!calendar_maker.employees_first);
+    }
 }
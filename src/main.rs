@@ -1,12 +1,13 @@
-use aubepine::CalendarMaker;
+use aubepine::{parse_iso_date, CalendarMaker, Event, TieBreakingStrategy};
 use clap::Parser;
+use itertools::Itertools;
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
     /// File path to the CSV file
-    #[arg(short, long)]
-    filename: String,
+    #[arg(short, long, required_unless_present = "generate_template")]
+    filename: Option<String>,
 
     /// Max number of subcontractors
     #[arg(short, long, default_value_t = 0)]
@@ -15,17 +16,133 @@ struct Args {
     // Verbosity
     #[arg(short, long, default_value_t = false)]
     verbose: bool,
+
+    /// Break the ties between persons on-call as many times randomly, with this seed to make runs reproducible
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Print the number of events each person is available for, per day, before solving
+    #[arg(long, default_value_t = false)]
+    heatmap: bool,
+
+    /// Only validate the input and report whether a calendar can be made, without solving it
+    #[arg(long, default_value_t = false)]
+    dry_run: bool,
+
+    /// Print the full report of the solver as JSON (calendar, statistics, fairness, subcontractors) instead of the
+    /// calendar
+    #[arg(long, default_value_t = false)]
+    json_report: bool,
+
+    /// Replace the names by codes in the calendar, and print the name of each code to stderr
+    #[arg(short, long, default_value_t = false, conflicts_with = "json_report")]
+    anonymize: bool,
+
+    /// Output format of the calendar
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Print a CSV file to fill in for these persons, separated by commas, from `--from` to `--to`, instead of solving
+    #[arg(long, value_delimiter = ',', requires_all = ["from", "to"])]
+    generate_template: Option<Vec<String>>,
+
+    /// First day of the template, e.g. 2025-05-05
+    #[arg(long)]
+    from: Option<String>,
+
+    /// Last day of the template, e.g. 2025-05-20
+    #[arg(long)]
+    to: Option<String>,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// A table, followed by the statistics if verbose
+    Text,
+    /// The calendar only, as JSON
+    Json,
 }
 
 fn main() {
     let args = Args::parse();
 
+    if let Some(names) = &args.generate_template {
+        let dates = [&args.from, &args.to].map(|date| date.as_deref().and_then(parse_iso_date));
+        let [Some(from), Some(to)] = dates else {
+            eprintln!("Invalid --from or --to date, expected e.g. 2025-05-05");
+            std::process::exit(1);
+        };
+        let events: &[Event] = &Event::ALL;
+        let persons = names
+            .iter()
+            .map(|name| (name.as_str(), events))
+            .collect::<Vec<_>>();
+        print!(
+            "{}",
+            CalendarMaker::generate_example_csv(from, to, &persons)
+        );
+        return;
+    }
+
     use std::time::Instant;
     let now = Instant::now();
 
-    let mut calendar_maker = CalendarMaker::from_file(&args.filename);
+    let mut calendar_maker = match CalendarMaker::try_from_file(args.filename.as_deref().unwrap()) {
+        Ok(calendar_maker) => calendar_maker,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    };
+    if let Some(seed) = args.seed {
+        calendar_maker.set_tie_breaking_strategy(TieBreakingStrategy::Random(seed));
+        calendar_maker.set_seed(seed);
+    }
+    if args.heatmap {
+        println!("{}", calendar_maker.availability_heatmap());
+    }
+    if args.dry_run {
+        for warning in calendar_maker.validate() {
+            println!("Warning: {}", warning);
+        }
+        let unfillable_slots = calendar_maker.report_infeasible_cells().len();
+        if unfillable_slots > args.subco as usize {
+            println!(
+                "Infeasible: {} day(s) / event(s) without anyone available, but only {} subcontractor(s) allowed",
+                unfillable_slots, args.subco
+            );
+            std::process::exit(1);
+        }
+        println!("Feasible");
+        return;
+    }
+    if args.json_report {
+        let report = calendar_maker.make_calendar_report(args.subco);
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        return;
+    }
+    #[cfg(feature = "progress")]
+    calendar_maker.make_calendar_with_progress_bar(args.subco, args.verbose);
+    #[cfg(not(feature = "progress"))]
     calendar_maker.make_calendar(args.subco, args.verbose);
-    println!("{}", calendar_maker.calendar_as_string());
+    let calendar = if args.anonymize {
+        let (calendar, names) = calendar_maker.calendar().anonymize();
+        for (code, name) in names.iter().sorted() {
+            eprintln!("{},{}", code, name);
+        }
+        calendar
+    } else {
+        calendar_maker.calendar().clone()
+    };
+    if args.format == Format::Json {
+        println!("{}", serde_json::to_string_pretty(&calendar).unwrap());
+        return;
+    }
+    println!("{}", calendar);
+    // The statistics are per person, so they would give the names away
+    if args.verbose && !args.anonymize {
+        calendar_maker.print_statistics();
+    }
 
     let elapsed = now.elapsed();
     println!("Elapsed: {:.2?}", elapsed);
@@ -0,0 +1,216 @@
+//! A person who can be on-call, with the attributes used by the assignment policies.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::calendar::Event;
+use crate::error::AubepineError;
+use crate::Name;
+
+/// Level of experience of a person. Some events may require a minimal seniority.
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Seniority {
+    #[default]
+    Junior,
+    Senior,
+    Expert,
+}
+
+/// Whether a person is part of the team, or an external resource added to fill the gaps.
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Membership {
+    #[default]
+    Employee,
+    Subcontractor,
+}
+
+/// Read from English or French, whatever the case, e.g. in a configuration file.
+impl FromStr for Membership {
+    type Err = AubepineError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "employee" | "salarié" => Ok(Membership::Employee),
+            "subcontractor" | "sous-traitant" => Ok(Membership::Subcontractor),
+            _ => Err(AubepineError::UnknownMembership(s.to_string())),
+        }
+    }
+}
+
+/// Ordered by name, then membership, so that persons can be the keys of a `BTreeMap`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Person {
+    // The order of the fields is the one of the comparison
+    name: Name,
+    membership: Membership,
+    seniority: Seniority,
+    max_hours_per_week: Option<u8>,
+    max_hours_per_month: Option<u16>,
+    min_rest_hours: Option<u16>,
+    preference_weight: u8,
+}
+
+/// Terms of the contract of a person, e.g. loaded from an HR system, see `CalendarMaker::solve_respecting_contracts`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractType {
+    /// The events the person may be on-call for
+    pub allowed_events: Vec<Event>,
+    /// Maximum number of on-call hours in a calendar month
+    pub max_monthly_hours: u16,
+    /// Maximum number of on-call hours in an ISO week
+    pub max_weekly_hours: u16,
+    /// Minimum number of hours between the end of an event and the start of the next one
+    pub min_rest_between_hours: u16,
+}
+
+impl Person {
+    /// Same as `new_employee`.
+    pub fn new(name: &str) -> Self {
+        Self::new_employee(name)
+    }
+
+    pub fn new_employee(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            membership: Membership::Employee,
+            seniority: Seniority::default(),
+            max_hours_per_week: None,
+            max_hours_per_month: None,
+            min_rest_hours: None,
+            preference_weight: 0,
+        }
+    }
+
+    pub fn new_subcontractor(name: &str) -> Self {
+        Self {
+            membership: Membership::Subcontractor,
+            ..Self::new_employee(name)
+        }
+    }
+
+    /// Person named as in the CSV file: a subcontractor if the name starts with `prefix`, e.g. `EXT-` for the ones
+    /// added by the solver, an employee otherwise.
+    pub fn from_csv_name(s: &str, prefix: &str) -> Self {
+        if s.starts_with(prefix) {
+            Self::new_subcontractor(s)
+        } else {
+            Self::new_employee(s)
+        }
+    }
+
+    pub fn name(&self) -> &Name {
+        &self.name
+    }
+
+    pub fn membership(&self) -> Membership {
+        self.membership
+    }
+
+    pub fn is_subcontractor(&self) -> bool {
+        self.membership == Membership::Subcontractor
+    }
+
+    pub fn seniority(&self) -> Seniority {
+        self.seniority
+    }
+
+    pub fn set_seniority(&mut self, seniority: Seniority) {
+        self.seniority = seniority;
+    }
+
+    pub fn max_hours_per_week(&self) -> Option<u8> {
+        self.max_hours_per_week
+    }
+
+    /// Limit the number of on-call hours of this person in any ISO week.
+    pub fn set_max_hours_per_week(&mut self, hours: u8) {
+        self.max_hours_per_week = Some(hours);
+    }
+
+    pub fn max_hours_per_month(&self) -> Option<u16> {
+        self.max_hours_per_month
+    }
+
+    /// Limit the number of on-call hours of this person in any calendar month.
+    pub fn set_max_hours_per_month(&mut self, hours: u16) {
+        self.max_hours_per_month = Some(hours);
+    }
+
+    pub fn min_rest_hours(&self) -> Option<u16> {
+        self.min_rest_hours
+    }
+
+    /// Require this number of hours between the end of an on-call event of this person and the start of the next one.
+    pub fn set_min_rest_hours(&mut self, hours: u16) {
+        self.min_rest_hours = Some(hours);
+    }
+
+    pub fn preference_weight(&self) -> u8 {
+        self.preference_weight
+    }
+
+    /// The higher the weight, the sooner this person is tried by the `BalancingStrategy::Priority`. 0 by default.
+    pub fn set_preference_weight(&mut self, weight: u8) {
+        self.preference_weight = weight;
+    }
+}
+
+impl fmt::Display for Person {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({:?})", self.name, self.membership)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_csv_name() {
+        let subco = Person::from_csv_name("EXT-0", "EXT-");
+        assert_eq!(subco.name(), "EXT-0");
+        assert!(subco.is_subcontractor());
+        let employee = Person::from_csv_name("ALI", "EXT-");
+        assert_eq!(employee.membership(), Membership::Employee);
+        assert_eq!(employee, Person::new("ALI"));
+    }
+
+    #[test]
+    fn test_membership_from_str() {
+        assert_eq!("Employee".parse(), Ok(Membership::Employee));
+        assert_eq!("Salarié".parse(), Ok(Membership::Employee));
+        assert_eq!("Subcontractor".parse(), Ok(Membership::Subcontractor));
+        assert_eq!("sous-traitant".parse(), Ok(Membership::Subcontractor));
+        assert_eq!(
+            "Intern".parse::<Membership>(),
+            Err(AubepineError::UnknownMembership("Intern".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_ord() {
+        let mut persons = vec![
+            Person::new("Bob"),
+            Person::new_subcontractor("Alice"),
+            Person::new("Alice"),
+        ];
+        persons.sort();
+        assert_eq!(
+            persons,
+            vec![
+                Person::new("Alice"),
+                Person::new_subcontractor("Alice"),
+                Person::new("Bob")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Person::new("Alice").to_string(), "Alice (Employee)");
+        assert_eq!(
+            Person::new_subcontractor("EXT-0").to_string(),
+            "EXT-0 (Subcontractor)"
+        );
+    }
+}
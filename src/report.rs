@@ -0,0 +1,178 @@
+//! Outcome of a run of the solver, owning everything needed to display or store it.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::time::Duration;
+
+use serde::{Serialize, Serializer};
+use time::Date;
+
+use crate::availabilities::Availabilities;
+use crate::calendar::{Calendar, Event};
+use crate::Name;
+
+/// Returned by `CalendarMaker::make_calendar_report`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduleReport {
+    pub calendar: Calendar,
+    /// Availabilities of each person once the calendar is made
+    pub availabilities: HashMap<Name, Availabilities>,
+    /// Number of times each day / event prevented the solver from finding a solution
+    #[serde(serialize_with = "serialize_problematic_days")]
+    pub problematic_days: BTreeMap<(Date, Event), u8>,
+    pub stats: SearchStats,
+    /// Subcontractors added during this run
+    pub subcontractors: Vec<Name>,
+    /// Standard deviation of the number of on-calls per person: the lower, the fairer
+    pub fairness_score: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchStats {
+    /// Serialized in seconds
+    #[serde(serialize_with = "serialize_duration")]
+    pub duration: Duration,
+    /// Number of days / events still empty once the solver is done
+    pub unfilled: usize,
+}
+
+/// As a list of `["2025-05-05", "FirstDaily", 3]`, JSON keys being strings only.
+fn serialize_problematic_days<S: Serializer>(
+    problematic_days: &BTreeMap<(Date, Event), u8>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(
+        problematic_days
+            .iter()
+            .map(|((day, event), count)| (day.to_string(), event, count)),
+    )
+}
+
+fn serialize_duration<S: Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+/// Returned by `CalendarMaker::solve_with_partial_result`.
+#[derive(Debug, Clone)]
+pub struct PartialSolveResult {
+    /// The full calendar if a solution is found, otherwise the one with the most days / events allocated
+    pub calendar: Calendar,
+    /// Days and events still empty in `calendar`
+    pub unfilled: Vec<(Date, Event)>,
+    pub subcontractors_added: u8,
+}
+
+/// Returned by `CalendarMaker::coverage_report`. Each field is `None` until `make_calendar` is called.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoverageReport {
+    /// Number of days with all the events allocated
+    pub fully_covered_days: Option<usize>,
+    /// Number of days with exactly one event still empty
+    pub days_with_one_unfilled: Option<usize>,
+    /// Number of days with two events or more still empty
+    pub days_with_several_unfilled: Option<usize>,
+    pub per_event: Option<BTreeMap<Event, EventCoverage>>,
+}
+
+/// Number of days an event is planned, i.e. the days of the calendar, and actually allocated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventCoverage {
+    pub planned: usize,
+    pub filled: usize,
+}
+
+/// One of the calendars returned by `CalendarMaker::solve_pareto`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParetoPoint {
+    /// Number of days / events given to subcontractors
+    pub subco_days: u8,
+    /// Fairness score of the calendar, see `ScheduleReport`, subcontractors excluded
+    pub fairness_score: f64,
+    pub calendar: Calendar,
+}
+
+/// Why a subcontractor was added by `CalendarMaker::make_calendar`, see `CalendarMaker::explain_subcontractor_need`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubcoExplanation {
+    pub name: Name,
+    /// Most problematic day / event of the failed search, given to the subcontractor
+    pub day: Date,
+    pub event: Event,
+    /// Number of persons still available for it in the best calendar of the failed search
+    pub available: usize,
+    /// Persons available for it at first, but on-call on the same or adjacent days in that calendar
+    pub blocked_by: Vec<Name>,
+}
+
+impl fmt::Display for SubcoExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} was added because {} / {:?} had {} available persons",
+            self.name, self.day, self.event, self.available
+        )?;
+        match self.blocked_by.split_last() {
+            None => write!(f, "."),
+            Some((last, [])) => write!(f, " after {} was assigned on adjacent days.", last),
+            Some((last, others)) => write!(
+                f,
+                " after {} and {} were assigned on adjacent days.",
+                others.join(", "),
+                last
+            ),
+        }
+    }
+}
+
+/// Returned by `CalendarMaker::simulate_absence`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationResult {
+    /// Whether all the days / events are still allocated
+    pub feasible: bool,
+    pub subcontractors_needed: u8,
+    /// Days and events which prevented the solver from finding a solution at some point
+    pub problematic: Vec<(Date, Event)>,
+    /// Change of the standard deviation of the number of on-calls per person: positive if less fair
+    pub fairness_delta: f64,
+}
+
+/// Passed to the callback of `CalendarMaker::set_on_progress` after each permutation of the events tried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchProgress {
+    pub permutations_tried: usize,
+    pub permutation_count: usize,
+    /// Subcontractors of the team, including the ones added so far by the solver
+    pub subcontractors: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subco_explanation_display() {
+        let mut explanation = SubcoExplanation {
+            name: "EXT-0".to_string(),
+            day: Date::from_ordinal_date(2025, 134).unwrap(),
+            event: Event::FirstNightly,
+            available: 0,
+            blocked_by: vec![],
+        };
+        assert_eq!(
+            explanation.to_string(),
+            "EXT-0 was added because 2025-05-14 / FirstNightly had 0 available persons."
+        );
+        explanation.blocked_by = vec![
+            "Alice".to_string(),
+            "Bob".to_string(),
+            "Charlie".to_string(),
+        ];
+        assert_eq!(
+            explanation.to_string(),
+            "EXT-0 was added because 2025-05-14 / FirstNightly had 0 available persons after Alice, Bob and Charlie were assigned on adjacent days."
+        );
+    }
+}
@@ -0,0 +1,48 @@
+//! Strategies of the solver, to choose between the persons available for a day.
+
+/// How the persons available for a day are ordered, the first one being tried first.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum BalancingStrategy {
+    /// The persons with the least on-call days first, whatever the event
+    #[default]
+    LeastOverall,
+    /// The persons with the least on-call days for the event being solved first
+    LeastForEvent,
+    /// The persons in alphabetical order, starting after the one on-call for the same event on the closest previous day
+    RoundRobin,
+    /// The persons with the highest preference weight first, see `Person::set_preference_weight`, then the ones with
+    /// the least on-call days
+    Priority,
+    /// The persons with the least on-call days first, including the ones of the previous calendars, see
+    /// `CalendarMaker::import_balance`
+    CarryOver,
+}
+
+/// How the persons are ordered when the balancing strategy ranks them equally.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum TieBreakingStrategy {
+    /// By name
+    #[default]
+    Alphabetical,
+    /// The persons on-call the most recently first
+    LastAssignedFirst,
+    /// The persons on-call the most recently last, the ones never on-call first
+    LastAssignedLast,
+    /// Shuffled, the same seed giving the same order, drawn from the generator of `CalendarMaker::set_seed` if set
+    Random(u64),
+}
+
+/// Which remaining day the solver fills first, the other days being ordered by the least available persons.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum FirstAssignmentStrategy {
+    /// The days with the least available persons first
+    #[default]
+    MostConstrained,
+    /// The last remaining day of the month first
+    DeadlineFirst,
+    /// The first remaining day of the month first
+    EarliestFirst,
+    /// A random remaining day first, the same seed giving the same order, drawn from the generator of
+    /// `CalendarMaker::set_seed` if set
+    Random(u64),
+}
@@ -0,0 +1,55 @@
+//! Helpers on dates, so that the code never relies on the ordinal of a day, which restarts every year.
+
+use time::Date;
+
+pub trait DateExt {
+    /// True if the day is between `from` and `to`, both included.
+    fn is_in_range(&self, from: Date, to: Date) -> bool;
+    /// Number of days from this day to `to`, negative if `to` is before.
+    fn days_until(&self, to: Date) -> i64;
+}
+
+impl DateExt for Date {
+    fn is_in_range(&self, from: Date, to: Date) -> bool {
+        *self >= from && *self <= to
+    }
+
+    fn days_until(&self, to: Date) -> i64 {
+        (to - *self).whole_days()
+    }
+}
+
+/// Parse a date such as 2025-05-05.
+pub fn parse_iso_date(s: &str) -> Option<Date> {
+    let mut parts = s.splitn(3, '-').map(|part| part.parse::<i32>().ok());
+    let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+    let month = time::Month::try_from(u8::try_from(month).ok()?).ok()?;
+    Date::from_calendar_date(year, month, u8::try_from(day).ok()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_across_years() {
+        let december_31 = Date::from_ordinal_date(2024, 366).unwrap();
+        let january_1 = Date::from_ordinal_date(2025, 1).unwrap();
+        let january_2 = Date::from_ordinal_date(2025, 2).unwrap();
+        assert_eq!(december_31.days_until(january_2), 2);
+        assert_eq!(january_2.days_until(december_31), -2);
+        assert!(january_1.is_in_range(december_31, january_2));
+        assert!(!december_31.is_in_range(january_1, january_2));
+    }
+
+    #[test]
+    fn test_parse_iso_date() {
+        assert_eq!(
+            parse_iso_date("2025-01-02"),
+            Some(Date::from_ordinal_date(2025, 2).unwrap())
+        );
+        assert_eq!(parse_iso_date("2025-02-30"), None);
+        assert_eq!(parse_iso_date("2025-01"), None);
+        assert_eq!(parse_iso_date("02/01/2025"), None);
+    }
+}
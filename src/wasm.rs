@@ -0,0 +1,24 @@
+//! JavaScript bindings of `CalendarMaker`, built with `wasm-pack` when the `wasm` feature is enabled.
+//! There's no file system in the browser, so the CSV content is passed as a string.
+
+use wasm_bindgen::prelude::*;
+
+use crate::CalendarMaker;
+
+#[wasm_bindgen]
+impl CalendarMaker {
+    #[wasm_bindgen(js_name = from_csv_string)]
+    pub fn js_from_csv_string(content: &str) -> CalendarMaker {
+        CalendarMaker::from_csv_string(content)
+    }
+
+    #[wasm_bindgen(js_name = make_calendar)]
+    pub fn js_make_calendar(&mut self, max_subco: u8) {
+        self.make_calendar(max_subco, false);
+    }
+
+    #[wasm_bindgen(js_name = calendar_as_string)]
+    pub fn js_calendar_as_string(&self) -> String {
+        self.calendar_as_string()
+    }
+}
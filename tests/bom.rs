@@ -0,0 +1,24 @@
+use aubepine::{AubepineError, CalendarMaker};
+
+#[test]
+fn test_utf8_bom_is_ignored() {
+    let with_bom = CalendarMaker::from_file("./tests/files/bom-test.csv");
+    let content = std::fs::read_to_string("./tests/files/bom-test.csv").unwrap();
+    let without_bom = CalendarMaker::from_csv_string(content.strip_prefix('\u{feff}').unwrap());
+    assert_eq!(with_bom.persons(), vec!["ALI", "AMA"]);
+    assert_eq!(with_bom.persons(), without_bom.persons());
+    assert_eq!(with_bom.calendar_range(), without_bom.calendar_range());
+    assert_eq!(
+        with_bom.export_availability_csv(),
+        without_bom.export_availability_csv()
+    );
+}
+
+#[test]
+fn test_utf16_is_an_error() {
+    match CalendarMaker::try_from_file("./tests/files/bom-utf16-test.csv") {
+        Err(AubepineError::InvalidCsv(reason)) => assert!(reason.contains("UTF-16")),
+        Err(error) => panic!("Unexpected error: {}", error),
+        Ok(_) => panic!("A UTF-16 file should not be parsed"),
+    }
+}
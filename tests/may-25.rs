@@ -5,7 +5,12 @@ fn test_main_for_may_2025() {
     let mut calendar_maker = CalendarMaker::from_file("./tests/files/mai-25-15j.csv");
     let max_subco = 2;
     let verbose = false;
+    let person_count = calendar_maker.person_count();
     calendar_maker.make_calendar(max_subco, verbose);
+    calendar_maker.assert_no_double_booking();
+    // No subcontractor is needed
+    assert_eq!(calendar_maker.person_count(), person_count);
+    assert!(calendar_maker.persons().is_sorted());
     let mut expected_calendar = "     |  05  |  06  |  07  |  08  |  09  |  10  |  11  |  12  |  13  |  14  |  15  |  16  |  17  |  18  |  19  |  20  |\r\n".to_string();
     expected_calendar.push_str("----------------------------------------------------------------------------------------------------------------------\r\n");
     expected_calendar.push_str("J    | AST  | CIN  | AMA  | CAR  | MEL  | LUX  | ELF  | ALI  | JUL  | AFI  | JEK  | SOS  | ALI  | CAR  | AFI  | AST  |\r\n");
@@ -17,3 +22,31 @@ fn test_main_for_may_2025() {
         calendar_maker.calendar_as_string()
     );
 }
+
+#[test]
+fn test_balance_for_may_2025() {
+    let mut calendar_maker = CalendarMaker::from_file("./tests/files/mai-25-15j.csv");
+    calendar_maker.set_seed(2);
+    // AFI and ALI are on vacation for more than half of the period, so the first calendar found may leave them far
+    // behind the others: keep the most balanced of several runs, each breaking the ties in another order
+    let spread = calendar_maker
+        .solve_n_times(20, 0)
+        .iter()
+        .map(|(calendar, _)| {
+            let (_, busiest_count) = calendar.busiest_person().unwrap();
+            let (_, least_busy_count) = calendar.least_busy_person().unwrap();
+            busiest_count - least_busy_count
+        })
+        .min()
+        .unwrap();
+    assert!(spread <= 2);
+}
+
+#[test]
+fn test_worst_case_solve_for_may_2025() {
+    let mut calendar_maker = CalendarMaker::from_file("./tests/files/mai-25-15j.csv");
+    calendar_maker.worst_case_solve(2);
+    calendar_maker.assert_no_double_booking();
+    let coverage = calendar_maker.coverage_report();
+    assert_eq!(coverage.fully_covered_days, Some(16));
+}
@@ -0,0 +1,42 @@
+use aubepine::{CalendarMaker, Event, ScheduleError};
+
+/// One person available for all the events, from monday 6th to sunday 12th of January 2025.
+const SINGLE_PERSON: &str = "JANVIER,2025,6,7,8,9,10,11,12\r\n\
+    ALI,1ère SF jour,,,,,,,\r\n\
+    ALI,1ère SF nuit,,,,,,,\r\n\
+    ALI,2ème SF jour,,,,,,,\r\n\
+    ALI,2ème SF nuit,,,,,,,\r\n";
+
+#[test]
+fn test_single_person_is_infeasible() {
+    let mut calendar_maker = CalendarMaker::from_csv_string(SINGLE_PERSON);
+    // She is available everywhere, so the search runs, but she can't be on-call every day for every event
+    assert!(calendar_maker.report_infeasible_cells().is_empty());
+    match calendar_maker.try_make_calendar(0, false) {
+        Err(ScheduleError::Unfilled(unfilled)) => assert!(!unfilled.is_empty()),
+        result => panic!("Expected unfilled days / events, got {:?}", result),
+    }
+    // No partial result is kept
+    let coverage = calendar_maker.coverage_report();
+    assert_eq!(coverage.fully_covered_days, Some(0));
+    assert_eq!(coverage.days_with_several_unfilled, Some(7));
+    assert_eq!(calendar_maker.person_count(), 1);
+}
+
+#[test]
+fn test_single_person_reports_unfilled_slots() {
+    let mut calendar_maker = CalendarMaker::from_csv_string(SINGLE_PERSON);
+    match calendar_maker.solve_with_budget_constraint(0) {
+        Err(ScheduleError::Unfilled(unfilled)) => assert!(!unfilled.is_empty()),
+        result => panic!("Expected unfilled days / events, got {:?}", result),
+    }
+    // The calendar maker is left untouched
+    for event in [
+        Event::FirstDaily,
+        Event::FirstNightly,
+        Event::SecondDaily,
+        Event::SecondNightly,
+    ] {
+        assert_eq!(calendar_maker.calendar().get_empty_days(&event).len(), 7);
+    }
+}
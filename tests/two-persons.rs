@@ -0,0 +1,69 @@
+use aubepine::{CalendarMaker, Event};
+use time::{Date, Month};
+
+/// Two persons per event, each available every day for her event only, from monday 6th to sunday 12th of
+/// January 2025.
+fn two_persons_per_event() -> String {
+    let mut content = "JANVIER,2025,6,7,8,9,10,11,12\r\n".to_string();
+    let events = [
+        "1ère SF jour",
+        "1ère SF nuit",
+        "2ème SF jour",
+        "2ème SF nuit",
+    ];
+    let pairs = [
+        ("ALI", "AMA"),
+        ("AST", "CAR"),
+        ("CIN", "ELF"),
+        ("JEK", "JUL"),
+    ];
+    for (i, (first, second)) in pairs.iter().enumerate() {
+        for name in [first, second] {
+            for (j, event) in events.iter().enumerate() {
+                let cells = if i == j { ",,,,,," } else { "x,x,x,x,x,x,x" };
+                content.push_str(&format!("{},{},{}\r\n", name, event, cells));
+            }
+        }
+    }
+    content
+}
+
+#[test]
+fn test_two_persons_alternate() {
+    let mut calendar_maker = CalendarMaker::from_csv_string(&two_persons_per_event());
+    calendar_maker.make_calendar(0, false);
+    calendar_maker.assert_no_double_booking();
+    assert!(calendar_maker
+        .check_consecutive_day_rule_violations()
+        .is_empty());
+    let calendar = calendar_maker.calendar();
+    let monday = Date::from_calendar_date(2025, Month::January, 6).unwrap();
+    let friday = Date::from_calendar_date(2025, Month::January, 10).unwrap();
+    for event in [
+        Event::FirstDaily,
+        Event::FirstNightly,
+        Event::SecondDaily,
+        Event::SecondNightly,
+    ] {
+        assert!(calendar.get_empty_days(&event).is_empty());
+        // The Second level can be on-call on friday, saturday and sunday, so only the week days alternate
+        let last_day = if event.is_second_level() {
+            friday
+        } else {
+            calendar.to()
+        };
+        let mut day = monday;
+        while day < last_day {
+            let next_day = day.next_day().unwrap();
+            assert_ne!(
+                calendar.get_for(&day, &event),
+                calendar.get_for(&next_day, &event),
+                "Same person on-call for {:?} on {} and {}",
+                event,
+                day,
+                next_day
+            );
+            day = next_day;
+        }
+    }
+}
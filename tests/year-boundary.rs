@@ -0,0 +1,33 @@
+use aubepine::{CalendarMaker, Event};
+
+#[test]
+fn test_calendar_across_new_year() {
+    let mut calendar_maker = CalendarMaker::from_file("./tests/files/dec24-jan25-7j.csv");
+    let (from, to) = calendar_maker.calendar_range();
+    assert_eq!(
+        (from.to_string(), to.to_string()),
+        ("2024-12-30".to_string(), "2025-01-05".to_string())
+    );
+    let person_count = calendar_maker.person_count();
+    calendar_maker.make_calendar(0, false);
+    calendar_maker.assert_no_double_booking();
+    assert_eq!(calendar_maker.person_count(), person_count);
+    for event in [
+        Event::FirstDaily,
+        Event::FirstNightly,
+        Event::SecondDaily,
+        Event::SecondNightly,
+    ] {
+        assert!(calendar_maker.calendar().get_empty_days(&event).is_empty());
+    }
+    let allocated = calendar_maker
+        .calendar()
+        .get_all()
+        .values()
+        .map(|events| events.len())
+        .sum::<usize>();
+    assert_eq!(allocated, 7 * 4);
+    assert!(calendar_maker
+        .check_consecutive_day_rule_violations()
+        .is_empty());
+}